@@ -0,0 +1,57 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{AuthorEntry, LibraryService, StorageError};
+use crate::config::Config;
+
+pub struct AuthorCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl AuthorCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<AuthorCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(AuthorCommand { service })
+    }
+
+    // Returns every author with the books written, sorted by surname
+    //
+    pub fn index(&self) -> Vec<AuthorEntry> {
+        self.service.author_index()
+    }
+
+    // Makes a spelling stand for another name and saves the library
+    //
+    pub fn alias(&mut self, alias: &str, name: &str) -> Result<(), StorageError> {
+        self.service.set_alias(alias, name);
+        self.service.save()
+    }
+
+    // Forgets an alias and saves the library
+    //
+    // Returns false if there was no such alias.
+    //
+    pub fn unalias(&mut self, alias: &str) -> Result<bool, StorageError> {
+        let removed = self.service.remove_alias(alias);
+        if removed {
+            self.service.save()?;
+        }
+        Ok(removed)
+    }
+
+    // Returns the aliases with the names they stand for
+    //
+    pub fn aliases(&self) -> Vec<(String, String)> {
+        self.service
+            .aliases()
+            .iter()
+            .map(|(alias, name)| (alias.clone(), name.clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,45 @@
+use crate::books::google::{CacheStats, ResponseCache};
+use crate::config::Config;
+use std::io::Result as IoResult;
+use std::path::Path;
+
+pub struct CacheCommand {
+    cache: ResponseCache,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl CacheCommand {
+    // Opens the cache folder set in the Config provided,
+    // even when the cache is disabled
+    //
+    pub fn create(config: &Config) -> CacheCommand {
+        let cache = ResponseCache::new(&config.cache);
+
+        CacheCommand { cache }
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.cache.dir()
+    }
+
+    // Counts the cached responses, the expired ones and their size
+    //
+    pub fn stats(&self) -> IoResult<CacheStats> {
+        self.cache.stats()
+    }
+
+    // Removes every cached response, returning how many
+    //
+    pub fn clear(&self) -> IoResult<usize> {
+        self.cache.clear()
+    }
+
+    // Removes the expired cached responses, returning how many
+    //
+    pub fn prune(&self) -> IoResult<usize> {
+        self.cache.prune()
+    }
+}
@@ -0,0 +1,83 @@
+use super::{create_service, CommandError};
+use crate::books::google::HttpError;
+use crate::books::{Filter, Holding, Isbn, LibraryService, OutcomeStatus, Volume};
+use crate::config::Config;
+
+pub struct CopyCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl CopyCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<CopyCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(CopyCommand { service })
+    }
+
+    // Adds a copy of a book and saves the library
+    //
+    // The metadata of a book already in the library is not fetched
+    // again, only a new copy is recorded. A new book is fetched first.
+    //
+    // Returns the copy added and the volume it belongs to.
+    //
+    pub async fn add(
+        &mut self,
+        isbn: &Isbn,
+        holding: Holding,
+    ) -> Result<(Holding, Volume), CommandError> {
+        if let OutcomeStatus::Failed(e) = self.service.append_volume(isbn).await.status {
+            return Err(CommandError::from(e));
+        }
+
+        let holding = self.service.add_holding(isbn, holding)?.clone();
+        self.service.save()?;
+
+        let volume = self.service.volume(isbn).unwrap_or_default();
+        Ok((holding, volume))
+    }
+
+    // Changes the details of a copy and saves the library
+    //
+    pub fn update(&mut self, changes: &Holding) -> Result<Holding, CommandError> {
+        let holding = self.service.update_holding(changes)?.clone();
+        self.service.save()?;
+        Ok(holding)
+    }
+
+    // Removes a copy and saves the library
+    //
+    // Returns the ISBN of the book the copy belonged to.
+    //
+    pub fn remove(&mut self, id: &str) -> Result<Isbn, CommandError> {
+        let isbn = self.service.remove_holding(id)?;
+        self.service.save()?;
+        Ok(isbn)
+    }
+
+    // Returns the copies of a book, or of all the library books,
+    // with their volume
+    //
+    pub fn list(&self, isbn: Option<&Isbn>) -> Vec<(Volume, &Holding)> {
+        let volumes = match isbn {
+            Some(isbn) => self.service.volume(isbn).into_iter().collect(),
+            None => self.service.select(&Filter::default()),
+        };
+
+        volumes
+            .into_iter()
+            .flat_map(|volume| {
+                self.service
+                    .holdings(&volume.isbn)
+                    .iter()
+                    .map(move |holding| (volume.clone(), holding))
+            })
+            .collect()
+    }
+}
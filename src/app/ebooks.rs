@@ -0,0 +1,178 @@
+use super::{create_service, CommandError};
+use crate::books::epub::Package;
+use crate::books::google::HttpError;
+use crate::books::{LibraryService, Volume};
+use crate::config::Config;
+use std::fs::{create_dir_all, read_dir, write};
+use std::io::Result as IoResult;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+pub struct EbooksCommand {
+    service: LibraryService,
+    covers: PathBuf,
+}
+
+/// Summary of an ebook folder scan
+///
+#[derive(Debug, Default)]
+pub struct EbookStats {
+    pub found: usize,
+    pub added: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl EbooksCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    // Covers are extracted in a `covers` folder next to the library.
+    //
+    pub fn create(config: &Config) -> Result<EbooksCommand, HttpError> {
+        let service = create_service(config)?;
+        let covers = Path::new(&config.output)
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join("covers");
+
+        Ok(EbooksCommand { service, covers })
+    }
+
+    // Builds a library volume from every EPUB in the folder and
+    // its subfolders, reading the package metadata. Volumes keep
+    // the absolute path of their file.
+    //
+    // EPUB already in the library, by file or by ISBN, are skipped.
+    // When enrich is set, volumes with an ISBN are completed with
+    // the data found online.
+    //
+    // Fails without touching the library when the folder cannot
+    // be read.
+    //
+    pub async fn run(
+        &mut self,
+        dir: &Path,
+        enrich: bool,
+    ) -> Result<EbookStats, CommandError> {
+        let mut stats = EbookStats::default();
+        let files = list_epubs(dir).map_err(|e| {
+            CommandError::UnreadableFolder(dir.display().to_string(), e.to_string())
+        })?;
+
+        for file in files.iter() {
+            stats.found += 1;
+            let file = file.canonicalize().unwrap_or_else(|_| file.clone());
+            let name = file.display().to_string();
+
+            if self.service.find_file(&name).is_some() {
+                stats.skipped += 1;
+                continue;
+            }
+
+            let volume = match self.create_volume(&file, enrich).await {
+                Ok(volume) => volume,
+                Err(e) => {
+                    stats.failed += 1;
+                    eprintln!("{}: {}", name, e);
+                    continue;
+                }
+            };
+
+            let title = volume.title.clone();
+            let isbn = volume.isbn.clone();
+            if self.service.add_volume(volume) {
+                stats.added += 1;
+                println!("Added {}: {}", isbn, title);
+            } else {
+                stats.skipped += 1;
+                eprintln!("{}: {} already in the library", name, isbn);
+            }
+        }
+
+        self.service.save()?;
+        Ok(stats)
+    }
+
+    // Read the EPUB metadata and cover, then enrich them if requested
+    //
+    async fn create_volume(&self, file: &Path, enrich: bool) -> Result<Volume, String> {
+        let package = Package::open(file).map_err(|e| e.to_string())?;
+        let mut volume = package.to_volume();
+        volume.file = Some(file.display().to_string());
+
+        if let Some(image) = self.save_cover(&package, file, &volume) {
+            volume.image = image;
+        }
+
+        if enrich && !volume.isbn.is_synthetic() {
+            match self.service.search(&volume.isbn).await {
+                Ok(online) => volume.fill_from(&online),
+                Err(e) => warn!(isbn = %volume.isbn, error = %e, "cannot enrich volume"),
+            }
+        }
+
+        Ok(volume)
+    }
+
+    // Extract the cover in the covers folder, named after the volume key
+    //
+    fn save_cover(
+        &self,
+        package: &Package,
+        file: &Path,
+        volume: &Volume,
+    ) -> Option<String> {
+        let data = match package.read_cover(file) {
+            Ok(data) => data?,
+            Err(e) => {
+                warn!(file = %file.display(), error = %e, "cannot read cover");
+                return None;
+            }
+        };
+
+        let extension = package
+            .cover
+            .as_deref()
+            .and_then(|cover| Path::new(cover).extension())
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("jpg");
+        let path = self.covers.join(format!("{}.{}", volume.isbn, extension));
+
+        match create_dir_all(&self.covers).and_then(|_| write(&path, data)) {
+            Ok(_) => Some(path.display().to_string()),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "cannot save cover");
+                None
+            }
+        }
+    }
+}
+
+// List the EPUB files in a folder and its subfolders, sorted by path
+//
+fn list_epubs(dir: &Path) -> IoResult<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(list_epubs(&path)?);
+        } else if is_epub(&path) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn is_epub(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("epub"))
+        .unwrap_or(false)
+}
@@ -0,0 +1,55 @@
+use super::{create_service, CommandError};
+use crate::books::google::HttpError;
+use crate::books::{Isbn, LibraryService, Override};
+use crate::config::Config;
+
+pub struct EditCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl EditCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<EditCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(EditCommand { service })
+    }
+
+    // Sets and drops the fields of a book by hand and saves the library
+    //
+    // Nothing is saved when a change is not valid.
+    //
+    // Returns the fields set by hand on the book after the changes.
+    //
+    pub fn run(
+        &mut self,
+        isbn: &Isbn,
+        set: &[(String, String)],
+        unset: &[String],
+    ) -> Result<Vec<Override>, CommandError> {
+        for field in unset.iter() {
+            self.service.remove_override(isbn, field)?;
+        }
+        for (field, value) in set.iter() {
+            self.service.set_override(isbn, field, value)?;
+        }
+        self.service.save()?;
+
+        Ok(self
+            .list()
+            .into_iter()
+            .filter(|entry| &entry.isbn == isbn)
+            .collect())
+    }
+
+    // Returns the fields set by hand on all the library books
+    //
+    pub fn list(&self) -> Vec<Override> {
+        self.service.overrides()
+    }
+}
@@ -0,0 +1,56 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{FetchObserver, LibraryService, StorageError};
+use crate::config::Config;
+use crate::input::Input;
+use std::rc::Rc;
+
+pub use crate::books::AppendStats as Stats;
+
+pub struct FetchCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl FetchCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    // The config usually is used to define the auth data for the client
+    // and the storage folder where to save output library data
+    //
+    pub fn create(config: &Config) -> Result<FetchCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(FetchCommand { service })
+    }
+
+    // Set an observer notified while the volumes are fetched,
+    // e.g. to display the progress.
+    //
+    pub fn observe(&mut self, observer: Rc<dyn FetchObserver>) {
+        self.service.set_observer(observer);
+    }
+
+    // Fetches new volumes based on the input list.
+    //
+    // It returns a stat object with the input data count and the volume
+    // appended. These values could be different (input is filtered).
+    //
+    // The input labels are put on the books in the library after the
+    // fetch, the new ones and the ones already there.
+    //
+    pub async fn run(&mut self, input: &Input) -> Result<Stats, StorageError> {
+        let stat = self.service.append_volumes(&input.isbns).await;
+        for isbn in input.isbns.iter() {
+            if let Some(labels) = input.labels.get(&isbn.to_string()) {
+                self.service.label(isbn, labels);
+            }
+        }
+        self.service.save()?;
+
+        Ok(stat)
+    }
+}
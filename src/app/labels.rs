@@ -0,0 +1,106 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{Isbn, Labels, LibraryService, StorageError};
+use crate::config::Config;
+
+pub struct LabelCommand {
+    service: LibraryService,
+    kind: LabelKind,
+}
+
+/// What a label command works on
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LabelKind {
+    Tag,
+    Shelf,
+}
+
+/// Summary of a label change
+///
+/// - changed: books found in the library and updated, leaving
+///   out the ones which already had, or did not have, the label
+/// - missing: ISBN not in the library
+///
+#[derive(Debug, Default)]
+pub struct LabelStats {
+    pub changed: usize,
+    pub missing: Vec<Isbn>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LabelCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config, kind: LabelKind) -> Result<LabelCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(LabelCommand { service, kind })
+    }
+
+    // Puts the tag or the shelf on the books and saves the library
+    //
+    pub fn add(
+        &mut self,
+        name: &str,
+        isbns: &[Isbn],
+    ) -> Result<LabelStats, StorageError> {
+        let labels = self.labels(name);
+        self.update(isbns, |service, isbn| service.label(isbn, &labels))
+    }
+
+    // Takes the tag or the shelf off the books and saves the library
+    //
+    pub fn remove(
+        &mut self,
+        name: &str,
+        isbns: &[Isbn],
+    ) -> Result<LabelStats, StorageError> {
+        let labels = self.labels(name);
+        self.update(isbns, |service, isbn| service.unlabel(isbn, &labels))
+    }
+
+    // Returns the tags or the shelves in use with their books count
+    //
+    pub fn counts(&self) -> Vec<(String, usize)> {
+        match self.kind {
+            LabelKind::Tag => self.service.tag_counts(),
+            LabelKind::Shelf => self.service.shelf_counts(),
+        }
+    }
+
+    fn labels(&self, name: &str) -> Labels {
+        let mut labels = Labels::default();
+        match self.kind {
+            LabelKind::Tag => labels.add_tag(name),
+            LabelKind::Shelf => labels.add_shelf(name),
+        }
+        labels
+    }
+
+    fn update<F>(
+        &mut self,
+        isbns: &[Isbn],
+        mut change: F,
+    ) -> Result<LabelStats, StorageError>
+    where
+        F: FnMut(&mut LibraryService, &Isbn) -> Option<bool>,
+    {
+        let mut stats = LabelStats::default();
+        for isbn in isbns.iter() {
+            match change(&mut self.service, isbn) {
+                Some(true) => stats.changed += 1,
+                Some(false) => (),
+                None => stats.missing.push(isbn.clone()),
+            }
+        }
+
+        if stats.changed > 0 {
+            self.service.save()?;
+        }
+        Ok(stats)
+    }
+}
@@ -0,0 +1,67 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{Filter, LibraryService, Volume};
+use crate::config::Config;
+
+pub struct ListCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl ListCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<ListCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(ListCommand { service })
+    }
+
+    // Prints the library books matching the filter, one per line,
+    // with their reading status, tags and shelves.
+    //
+    // Returns the count of the books printed.
+    //
+    pub fn run(&self, filter: &Filter) -> usize {
+        let volumes = self.service.select(filter);
+        for volume in volumes.iter() {
+            println!("{}", self.describe(volume));
+        }
+        volumes.len()
+    }
+
+    // One line description: ISBN, title, authors, [reading status],
+    // (copies count), #tags and @shelves
+    //
+    fn describe(&self, volume: &Volume) -> String {
+        let mut line = format!("{}  {}", volume.isbn, volume.title);
+        if !volume.authors.is_empty() {
+            line.push_str(&format!(" - {}", volume.authors.join(", ")));
+        }
+
+        if let Some(status) = self
+            .service
+            .reading(&volume.isbn)
+            .and_then(|reading| reading.status)
+        {
+            line.push_str(&format!(" [{}]", status));
+        }
+
+        let copies = self.service.holdings(&volume.isbn).len();
+        if copies > 1 {
+            line.push_str(&format!(" ({} copies)", copies));
+        }
+
+        let labels = self.service.labels(&volume.isbn);
+        for tag in labels.tags.iter() {
+            line.push_str(&format!(" #{}", tag));
+        }
+        for shelf in labels.shelves.iter() {
+            line.push_str(&format!(" @{}", shelf));
+        }
+        line
+    }
+}
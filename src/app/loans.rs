@@ -0,0 +1,118 @@
+use super::{create_service, CommandError};
+use crate::books::date::today;
+use crate::books::google::HttpError;
+use crate::books::{Isbn, LibraryService, Loan, LoanError, Volume};
+use crate::calendar::{to_ics, Event};
+use crate::config::Config;
+use std::fs::write;
+use std::io::Result as IoResult;
+
+pub struct LoanCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LoanCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<LoanCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(LoanCommand { service })
+    }
+
+    // Lends a book, or one of its copies, and saves the library
+    //
+    pub fn lend(
+        &mut self,
+        isbn: &Isbn,
+        borrower: &str,
+        due: Option<&str>,
+        copy: Option<&str>,
+    ) -> Result<Loan, CommandError> {
+        let loan = self.service.lend(isbn, borrower, due, copy)?.clone();
+        self.service.save()?;
+        Ok(loan)
+    }
+
+    // Marks a book, or one of its copies, as returned
+    // and saves the library
+    //
+    pub fn give_back(
+        &mut self,
+        isbn: &Isbn,
+        copy: Option<&str>,
+    ) -> Result<Loan, CommandError> {
+        let loan = self.service.give_back(isbn, copy)?.clone();
+        self.service.save()?;
+        Ok(loan)
+    }
+
+    // Returns the loan history of a book, oldest first
+    //
+    pub fn history(&self, isbn: &Isbn) -> Result<&[Loan], LoanError> {
+        match self.service.find(isbn) {
+            Some(_) => Ok(self.service.loans(isbn)),
+            None => Err(LoanError::NotInLibrary(isbn.clone())),
+        }
+    }
+
+    // Returns the books out on loan
+    //
+    pub fn lent(&self) -> Vec<(Volume, &Loan)> {
+        self.service.lent_volumes()
+    }
+
+    // Returns the books out on loan after their due date
+    //
+    pub fn overdue(&self) -> Vec<(Volume, &Loan)> {
+        let today = today();
+        self.lent()
+            .into_iter()
+            .filter(|(_, loan)| loan.is_overdue(&today))
+            .collect()
+    }
+
+    // Writes the due dates of the books out on loan
+    // as an iCalendar file
+    //
+    // Returns the count of the events written.
+    //
+    pub fn export(&self, path: &str) -> IoResult<usize> {
+        let events: Vec<Event> = self
+            .lent()
+            .into_iter()
+            .filter_map(|(volume, loan)| {
+                // The position in the history tells apart the loans
+                // of the same copy made on the same day
+                let sequence = self
+                    .service
+                    .loans(&volume.isbn)
+                    .iter()
+                    .position(|other| std::ptr::eq(other, loan))
+                    .unwrap_or_default();
+                loan.due.as_ref().map(|due| Event {
+                    uid: format!(
+                        "{}-{}-{}-{}@books",
+                        volume.isbn,
+                        loan.copy.as_deref().unwrap_or_default(),
+                        loan.lent,
+                        sequence
+                    ),
+                    date: due.clone(),
+                    summary: format!("Return \"{}\" ({})", volume.title, loan.borrower),
+                    description: format!(
+                        "{} lent to {} on {}, ISBN {}",
+                        volume.title, loan.borrower, loan.lent, volume.isbn
+                    ),
+                })
+            })
+            .collect();
+
+        write(path, to_ics(&events))?;
+        Ok(events.len())
+    }
+}
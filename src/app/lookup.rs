@@ -0,0 +1,129 @@
+use super::{create_service, CommandError};
+use crate::books::google::{Candidate, HttpError};
+use crate::books::{LibraryService, Volume};
+use crate::config::Config;
+use crate::input::has_terminal_stdin;
+use std::io::{stderr, stdin, BufRead, Write};
+
+pub struct LookupCommand {
+    service: LibraryService,
+}
+
+/// Outcome of a title and author lookup
+///
+/// - Added: the chosen volume was stored in the library
+/// - Duplicate: the chosen volume was already in the library
+/// - NoCandidates: the search returned no volume
+/// - NotSelected: no candidate was accepted or chosen
+///
+#[derive(Debug)]
+pub enum LookupOutcome {
+    Added(Volume),
+    Duplicate(Volume),
+    NoCandidates,
+    NotSelected,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LookupCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<LookupCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(LookupCommand { service })
+    }
+
+    // Searches the books matching title and author and prints
+    // the ranked candidates.
+    //
+    // The best candidate is taken right away when its score reaches
+    // the accept threshold, otherwise the user picks one when stdin
+    // is a terminal. The chosen volume is stored with its ISBN, or
+    // with a synthetic key when it has none.
+    //
+    pub async fn run(
+        &mut self,
+        title: &str,
+        author: Option<&str>,
+        accept_above: Option<f64>,
+    ) -> Result<LookupOutcome, CommandError> {
+        let candidates = self.service.search_candidates(title, author).await?;
+        if candidates.is_empty() {
+            return Ok(LookupOutcome::NoCandidates);
+        }
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            println!("{:>3}. {}", index + 1, describe(candidate));
+        }
+
+        let best = &candidates[0];
+        let chosen = match accept_above {
+            Some(threshold) if best.score >= threshold => Some(best),
+            _ if has_terminal_stdin() => choose(&candidates),
+            _ => None,
+        };
+
+        let volume = match chosen {
+            Some(candidate) => candidate.volume.clone(),
+            None => return Ok(LookupOutcome::NotSelected),
+        };
+
+        if !self.service.add_volume(volume.clone()) {
+            return Ok(LookupOutcome::Duplicate(volume));
+        }
+        self.service.save()?;
+
+        Ok(LookupOutcome::Added(volume))
+    }
+}
+
+// One line description of a candidate: score, title, authors,
+// year, publisher and key.
+//
+fn describe(candidate: &Candidate) -> String {
+    let volume = &candidate.volume;
+    let mut line = format!("[{:.2}] {}", candidate.score, volume.title);
+
+    if !volume.authors.is_empty() {
+        line.push_str(&format!(" - {}", volume.authors.join(", ")));
+    }
+
+    let details: Vec<&str> = [candidate.year(), volume.publisher.as_str()]
+        .iter()
+        .copied()
+        .filter(|detail| !detail.is_empty())
+        .collect();
+    if !details.is_empty() {
+        line.push_str(&format!(" ({})", details.join(", ")));
+    }
+
+    format!("{} {}", line, volume.isbn)
+}
+
+// Asks the user which candidate to keep until a valid answer
+// is given. Zero, an empty answer or the end of stdin skip.
+//
+fn choose(candidates: &[Candidate]) -> Option<&Candidate> {
+    let input = stdin();
+    let mut lines = input.lock().lines();
+
+    loop {
+        eprint!("Choose a book [1-{}], 0 to skip: ", candidates.len());
+        let _ = stderr().flush();
+
+        let answer = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return None,
+        };
+        match answer.trim().parse::<usize>() {
+            Ok(0) => return None,
+            Ok(n) if n <= candidates.len() => return Some(&candidates[n - 1]),
+            _ if answer.trim().is_empty() => return None,
+            _ => eprintln!("Warning: \"{}\" is not a valid choice", answer.trim()),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+mod authors;
+mod cache;
+mod copies;
+mod ebooks;
+mod edit;
+mod fetch;
+mod labels;
+mod list;
+mod loans;
+mod lookup;
+mod reading;
+mod refresh;
+mod scan;
+mod series;
+mod stats;
+mod wishlist;
+mod works;
+
+use crate::books::google::{ApiConfig, Client, FetchError, HttpError};
+use crate::books::{
+    HoldingError, LibraryService, LoanError, OverrideError, ReadingError, Storage,
+    StorageError, WishError, WorkError,
+};
+use crate::config::Config;
+use std::rc::Rc;
+use thiserror::Error;
+
+pub use authors::AuthorCommand;
+pub use cache::CacheCommand;
+pub use copies::CopyCommand;
+pub use ebooks::{EbookStats, EbooksCommand};
+pub use edit::EditCommand;
+pub use fetch::{FetchCommand, Stats};
+pub use labels::{LabelCommand, LabelKind, LabelStats};
+pub use list::ListCommand;
+pub use loans::LoanCommand;
+pub use lookup::{LookupCommand, LookupOutcome};
+pub use reading::ReadingCommand;
+pub use refresh::RefreshCommand;
+pub use scan::{ScanCommand, ScanStats};
+pub use series::SeriesCommand;
+pub use stats::StatsCommand;
+pub use wishlist::WishCommand;
+pub use works::WorkCommand;
+
+/// Error of a command, wrapping the errors of the library
+/// service it runs
+///
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("Cannot read folder {0}: {1}")]
+    UnreadableFolder(String, String),
+    #[error(transparent)]
+    Fetch(#[from] FetchError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Loan(#[from] LoanError),
+    #[error(transparent)]
+    Reading(#[from] ReadingError),
+    #[error(transparent)]
+    Holding(#[from] HoldingError),
+    #[error(transparent)]
+    Override(#[from] OverrideError),
+    #[error(transparent)]
+    Work(#[from] WorkError),
+    #[error(transparent)]
+    Wish(#[from] WishError),
+}
+
+// Create the library service with its client and storage
+// dependencies using the Config provided.
+//
+fn create_service(config: &Config) -> Result<LibraryService, HttpError> {
+    let client = Rc::new(create_client(config)?);
+    let storage = Rc::new(create_storage(config));
+    Ok(LibraryService::new(client, storage))
+}
+
+// Extract data from config to create the client
+//
+// Pay attention to the fact the Config is considered
+// invalid if the base url is not set so we should have
+// it if this function is called. The api key can be empty.
+//
+// Fails when the HTTP settings are invalid, e.g. a malformed
+// proxy url or an unreadable certificate.
+//
+fn create_client(config: &Config) -> Result<Client, HttpError> {
+    let api_config = ApiConfig {
+        base_uri: config.base_url.clone(),
+        api_key: config.api_key.clone(),
+        country: config.country.clone(),
+        lang_restrict: config.lang_restrict.clone(),
+        print_type: config.print_type.clone(),
+        on_mismatch: config.on_isbn_mismatch,
+        http: config.http.clone(),
+        cache: config.cache.clone(),
+        record: config.record.clone(),
+    };
+    Client::new(api_config)
+}
+
+// Create the storage service passing where to put
+// the storage from Config.
+//
+fn create_storage(config: &Config) -> Storage {
+    Storage::new(&config.output)
+}
@@ -0,0 +1,44 @@
+use super::{create_service, CommandError};
+use crate::books::google::HttpError;
+use crate::books::{Isbn, LibraryService, Reading, ReadingUpdate};
+use crate::config::Config;
+
+pub struct ReadingCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl ReadingCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<ReadingCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(ReadingCommand { service })
+    }
+
+    // Returns the reading log of a book, an empty one when nothing
+    // was logged yet, or None if the book is not in the library
+    //
+    pub fn show(&self, isbn: &Isbn) -> Option<Reading> {
+        self.service.find(isbn)?;
+        Some(self.service.reading(isbn).cloned().unwrap_or_default())
+    }
+
+    // Updates the reading log of a book and saves the library
+    //
+    // Returns the updated log.
+    //
+    pub fn update(
+        &mut self,
+        isbn: &Isbn,
+        update: &ReadingUpdate,
+    ) -> Result<Reading, CommandError> {
+        let reading = self.service.update_reading(isbn, update)?.clone();
+        self.service.save()?;
+        Ok(reading)
+    }
+}
@@ -0,0 +1,46 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{FetchObserver, Isbn, LibraryService, Outcome, StorageError};
+use crate::config::Config;
+use std::rc::Rc;
+
+pub struct RefreshCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl RefreshCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<RefreshCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(RefreshCommand { service })
+    }
+
+    // Set an observer notified while the volumes are fetched
+    //
+    pub fn observe(&mut self, observer: Rc<dyn FetchObserver>) {
+        self.service.set_observer(observer);
+    }
+
+    // Fetches again the metadata of the given books, or of the whole
+    // library when none is given, and saves the library.
+    //
+    // Tags, shelves and reading logs are left as they are.
+    //
+    pub async fn run(&mut self, isbns: &[Isbn]) -> Result<Vec<Outcome>, StorageError> {
+        let isbns = match isbns.is_empty() {
+            true => self.service.isbns(),
+            false => isbns.to_vec(),
+        };
+
+        let outcomes = self.service.refresh_volumes(&isbns).await;
+        self.service.save()?;
+
+        Ok(outcomes)
+    }
+}
@@ -0,0 +1,109 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{Isbn, LibraryService, OutcomeStatus, StorageError};
+use crate::config::Config;
+use tokio::io::{stdin, AsyncBufReadExt, BufReader};
+use tokio::signal::ctrl_c;
+use tracing::warn;
+
+pub struct ScanCommand {
+    service: LibraryService,
+}
+
+/// Summary of a scan session
+///
+#[derive(Debug, Default)]
+pub struct ScanStats {
+    pub scanned: usize,
+    pub added: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl ScanCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<ScanCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(ScanCommand { service })
+    }
+
+    // Reads the codes typed by a keyboard-wedge scanner, one per line,
+    // until stdin is closed or the session is interrupted.
+    //
+    // Every book is fetched and the library saved right away, so
+    // an interruption never loses the books already scanned.
+    //
+    pub async fn run(&mut self) -> Result<ScanStats, StorageError> {
+        let mut stats = ScanStats::default();
+        let mut lines = BufReader::new(stdin()).lines();
+        let interrupt = ctrl_c();
+        tokio::pin!(interrupt);
+
+        loop {
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                _ = &mut interrupt => break,
+            };
+
+            match line {
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(line)) => self.scan(line.trim(), &mut stats).await?,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!(error = %e, "cannot read stdin");
+                    break;
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    // Validates, fetches and saves a single scanned code.
+    //
+    async fn scan(
+        &mut self,
+        code: &str,
+        stats: &mut ScanStats,
+    ) -> Result<(), StorageError> {
+        let isbn = Isbn::new(code);
+        stats.scanned += 1;
+
+        if !isbn.is_valid() {
+            stats.failed += 1;
+            eprintln!("Warning: \"{}\" is not a valid ISBN", code);
+            return Ok(());
+        }
+
+        let outcome = self.service.append_volume(&isbn).await;
+        let title = self
+            .service
+            .volume(&isbn)
+            .map(|volume| volume.title)
+            .unwrap_or_default();
+
+        match outcome.status {
+            OutcomeStatus::Fetched => {
+                self.service.save()?;
+                stats.added += 1;
+                println!("Added {}: {}", isbn, title);
+            }
+            OutcomeStatus::Skipped => {
+                stats.duplicates += 1;
+                eprintln!("Warning: {} already in the library: {}", isbn, title);
+            }
+            OutcomeStatus::Failed(e) => {
+                stats.failed += 1;
+                eprintln!("Warning: {} not found: {}", isbn, e);
+            }
+        };
+
+        Ok(())
+    }
+}
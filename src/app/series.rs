@@ -0,0 +1,28 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{LibraryService, SeriesEntry};
+use crate::config::Config;
+
+pub struct SeriesCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl SeriesCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<SeriesCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(SeriesCommand { service })
+    }
+
+    // Returns the series with their books in reading order
+    //
+    pub fn list(&self) -> Vec<SeriesEntry> {
+        self.service.series()
+    }
+}
@@ -0,0 +1,38 @@
+use super::create_service;
+use crate::books::google::HttpError;
+use crate::books::{LibraryService, LibraryStats};
+use crate::config::Config;
+use crate::html::to_html;
+use std::fs::write;
+use std::io::Result as IoResult;
+
+pub struct StatsCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl StatsCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<StatsCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(StatsCommand { service })
+    }
+
+    // Returns the statistics of the library books
+    //
+    pub fn stats(&self) -> LibraryStats {
+        self.service.stats()
+    }
+
+    // Writes the statistics as a self-contained HTML page,
+    // charting the `top` most frequent publishers and authors
+    //
+    pub fn export(&self, path: &str, top: usize) -> IoResult<()> {
+        write(path, to_html(&self.stats(), top))
+    }
+}
@@ -0,0 +1,74 @@
+use super::{create_service, CommandError};
+use crate::books::google::HttpError;
+use crate::books::{Holding, Isbn, LibraryService, OutcomeStatus, Wish, WishError};
+use crate::config::Config;
+
+pub struct WishCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl WishCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<WishCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(WishCommand { service })
+    }
+
+    // Fetches a book, adds it to the wishlist and saves the library
+    //
+    // Returns the wish, and false if it was already in the wishlist.
+    //
+    pub async fn add(
+        &mut self,
+        isbn: &Isbn,
+        note: Option<String>,
+    ) -> Result<(Wish, bool), CommandError> {
+        let outcome = self.service.add_wish(isbn, note).await?;
+        if let OutcomeStatus::Failed(e) = outcome.status {
+            return Err(CommandError::from(e));
+        }
+        self.service.save()?;
+
+        let wish = self
+            .service
+            .wishes()
+            .iter()
+            .find(|wish| &wish.volume.isbn == isbn)
+            .cloned()
+            .ok_or_else(|| WishError::NotWished(isbn.clone()))?;
+        Ok((wish, matches!(outcome.status, OutcomeStatus::Fetched)))
+    }
+
+    // Moves a wished book to the library with a new copy
+    // and saves the library
+    //
+    pub fn acquire(
+        &mut self,
+        isbn: &Isbn,
+        holding: Holding,
+    ) -> Result<Holding, CommandError> {
+        let holding = self.service.acquire(isbn, holding)?.clone();
+        self.service.save()?;
+        Ok(holding)
+    }
+
+    // Takes a book out of the wishlist and saves the library
+    //
+    pub fn remove(&mut self, isbn: &Isbn) -> Result<Wish, CommandError> {
+        let wish = self.service.remove_wish(isbn)?;
+        self.service.save()?;
+        Ok(wish)
+    }
+
+    // Returns the wished books, oldest first
+    //
+    pub fn list(&self) -> &[Wish] {
+        self.service.wishes()
+    }
+}
@@ -0,0 +1,53 @@
+use super::{create_service, CommandError};
+use crate::books::google::HttpError;
+use crate::books::{Duplicate, Isbn, LibraryService, Work};
+use crate::config::Config;
+
+pub struct WorkCommand {
+    service: LibraryService,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl WorkCommand {
+    // Creates the services dependencies using the Config provided
+    //
+    pub fn create(config: &Config) -> Result<WorkCommand, HttpError> {
+        let service = create_service(config)?;
+
+        Ok(WorkCommand { service })
+    }
+
+    // Returns the works with more than one edition, grouping
+    // the unlinked books at least `min_score` similar
+    //
+    pub fn works(&self, min_score: f64) -> Vec<Work> {
+        self.service.works(min_score)
+    }
+
+    // Returns the likely duplicates, most similar first
+    //
+    pub fn duplicates(&self, min_score: f64) -> Vec<Duplicate> {
+        self.service.duplicates(min_score)
+    }
+
+    // Folds the books in one work and saves the library
+    //
+    // Returns the count of the editions of the work.
+    //
+    pub fn merge(&mut self, isbns: &[Isbn]) -> Result<usize, CommandError> {
+        let count = self.service.merge_works(isbns)?;
+        self.service.save()?;
+        Ok(count)
+    }
+
+    // Takes a book out of its work and saves the library
+    //
+    pub fn unmerge(&mut self, isbn: &Isbn) -> Result<(), CommandError> {
+        self.service.unmerge_work(isbn)?;
+        self.service.save()?;
+        Ok(())
+    }
+}
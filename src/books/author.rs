@@ -0,0 +1,160 @@
+//! Author name normalization.
+//!
+//! Providers spell the same author in many ways, e.g. "J.R.R. Tolkien",
+//! "J. R. R. Tolkien" or "Tolkien, J.R.R.". Every spelling maps to a
+//! sort name, surname first, and to a key without case, accents and
+//! punctuation which is the same for all of them.
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Words belonging to the surname when before it, e.g. "van" in
+/// "Ludwig van Beethoven"
+///
+const PARTICLES: [&str; 16] = [
+    "da", "de", "del", "della", "den", "der", "di", "dos", "du", "la", "le", "st.",
+    "ten", "ter", "van", "von",
+];
+
+/// Generational suffixes closing a name, e.g. "Jr." in
+/// "Martin Luther King Jr."
+///
+const SUFFIXES: [&str; 5] = ["jr", "sr", "ii", "iii", "iv"];
+
+/// An author name split in surname, given names and
+/// generational suffix
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorName {
+    pub surname: String,
+    pub given: String,
+    pub suffix: String,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl AuthorName {
+    /// Split a name written either as "Given Surname"
+    /// or as "Surname, Given". Initials are spaced and
+    /// end with a dot, e.g. "J.R.R." becomes "J. R. R.".
+    /// A closing suffix such as "Jr." or "III" is set apart
+    /// before picking the surname.
+    ///
+    pub fn parse(name: &str) -> AuthorName {
+        let (name, suffix) = split_suffix(name);
+        let (surname, given) = match name.split_once(',') {
+            Some((surname, given)) => (words(surname), words(given)),
+            None => {
+                let mut words = words(&name);
+                let mut start = words.len().saturating_sub(1);
+                while start > 1
+                    && PARTICLES.contains(&words[start - 1].to_lowercase().as_str())
+                {
+                    start -= 1;
+                }
+                let surname = words.split_off(start);
+                (surname, words)
+            }
+        };
+
+        AuthorName {
+            surname: surname.join(" "),
+            given: given.join(" "),
+            suffix,
+        }
+    }
+
+    /// Name surname first, e.g. "Tolkien, J. R. R."
+    /// or "King, Martin Luther, Jr."
+    ///
+    pub fn sort_name(&self) -> String {
+        let mut name = self.surname.clone();
+        for part in [&self.given, &self.suffix] {
+            if !part.is_empty() {
+                name = format!("{}, {}", name, part);
+            }
+        }
+        name
+    }
+
+    /// Key shared by all the spellings of the name:
+    /// surname first, lowercase, without accents and punctuation
+    ///
+    pub fn key(&self) -> String {
+        let name = format!("{} {} {}", self.surname, self.given, self.suffix);
+        let folded: String = name
+            .to_lowercase()
+            .nfd()
+            .filter(|c| !is_combining_mark(*c))
+            .map(|c| match c.is_alphanumeric() {
+                true => fold(c),
+                false => String::from(" "),
+            })
+            .collect();
+        folded.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+// Splits the generational suffix off a name, written either
+// as the last word or after a last comma
+//
+fn split_suffix(name: &str) -> (String, String) {
+    let is_suffix = |word: &str| {
+        SUFFIXES.contains(&word.trim().trim_end_matches('.').to_lowercase().as_str())
+    };
+
+    if let Some((rest, last)) = name.rsplit_once(',') {
+        if is_suffix(last) && !rest.trim().is_empty() {
+            return (rest.to_string(), last.trim().to_string());
+        }
+    }
+    let words: Vec<&str> = name.split_whitespace().collect();
+    match words.split_last() {
+        Some((last, rest)) if rest.len() > 1 && is_suffix(last) => {
+            (rest.join(" "), last.to_string())
+        }
+        _ => (name.to_string(), String::new()),
+    }
+}
+
+// Splits a name in words, spacing the initials
+//
+fn words(name: &str) -> Vec<String> {
+    name.split_whitespace()
+        .flat_map(|word| {
+            let parts: Vec<&str> = word.split_inclusive('.').collect();
+            let initials = parts.len() > 1
+                && parts
+                    .iter()
+                    .all(|part| part.ends_with('.') && part.chars().count() == 2);
+            match initials {
+                true => parts.iter().map(|part| part.to_string()).collect(),
+                false => vec![word.to_string()],
+            }
+        })
+        .map(|word| match word.chars().count() {
+            1 if word.chars().all(char::is_alphabetic) => format!("{}.", word),
+            _ => word,
+        })
+        .collect()
+}
+
+// Spells the letters left whole by the canonical decomposition,
+// e.g. "ø" or "ł", with plain latin letters
+//
+fn fold(c: char) -> String {
+    let folded = match c {
+        'đ' => "d",
+        'ı' => "i",
+        'ł' => "l",
+        'ø' => "o",
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'þ' => "th",
+        _ => return c.to_string(),
+    };
+    folded.to_string()
+}
@@ -0,0 +1,126 @@
+//! Calendar dates as YYYY-MM-DD strings, in UTC.
+//!
+//! Dates are kept as text in the library, so they sort and
+//! compare as strings.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// True if the date is written as YYYY-MM-DD and exists
+/// in the calendar
+///
+pub fn is_valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let number = |part: &str, len: usize| {
+        Some(part)
+            .filter(|part| part.len() == len && part.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|part| part.parse::<u32>().ok())
+    };
+
+    match parts.as_slice() {
+        [year, month, day] => match (number(year, 4), number(month, 2), number(day, 2)) {
+            (Some(year), Some(month), Some(day)) => {
+                (1..=12).contains(&month)
+                    && (1..=days_in_month(year, month)).contains(&day)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The day after a YYYY-MM-DD date, None when the date
+/// is not valid
+///
+pub fn next_day(date: &str) -> Option<String> {
+    if !is_valid_date(date) {
+        return None;
+    }
+    let year: u32 = date[0..4].parse().ok()?;
+    let month: u32 = date[5..7].parse().ok()?;
+    let day: u32 = date[8..10].parse().ok()?;
+
+    let (year, month, day) = match (month, day) {
+        (12, 31) => (year + 1, 1, 1),
+        (_, day) if day == days_in_month(year, month) => (year, month + 1, 1),
+        _ => (year, month, day + 1),
+    };
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Current date as YYYY-MM-DD
+///
+pub fn today() -> String {
+    let (year, month, day) = civil(now() / 86400);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Current date and time as an iCalendar UTC timestamp,
+/// e.g. 20240131T093000Z
+///
+pub fn timestamp() -> String {
+    let now = now();
+    let (year, month, day) = civil(now / 86400);
+    let seconds = now % 86400;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    let leap =
+        year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default() as i64
+}
+
+// Civil date from the days since the epoch (Howard Hinnant's algorithm)
+//
+fn civil(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_day_rolls_over_months_and_years() {
+        assert_eq!(next_day("2024-03-01").as_deref(), Some("2024-03-02"));
+        assert_eq!(next_day("2024-04-30").as_deref(), Some("2024-05-01"));
+        assert_eq!(next_day("2024-12-31").as_deref(), Some("2025-01-01"));
+    }
+
+    #[test]
+    fn next_day_follows_leap_years() {
+        assert_eq!(next_day("2024-02-28").as_deref(), Some("2024-02-29"));
+        assert_eq!(next_day("2023-02-28").as_deref(), Some("2023-03-01"));
+        assert_eq!(next_day("2023-02-29"), None);
+    }
+}
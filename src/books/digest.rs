@@ -0,0 +1,15 @@
+/// FNV-1a 64 bit offset basis and prime
+///
+const OFFSET: u64 = 0xcbf29ce484222325;
+const PRIME: u64 = 0x100000001b3;
+
+/// Stable 64 bit hash of a string.
+///
+/// Unlike the std hashers the value never changes between
+/// builds, so it can be persisted as a key.
+///
+pub fn fnv1a(data: &str) -> u64 {
+    data.bytes().fold(OFFSET, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
@@ -0,0 +1,285 @@
+use super::digest::fnv1a;
+use super::{Isbn, Volume};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use thiserror::Error;
+use zip::ZipArchive;
+
+/// Location of the container file pointing to the package document
+///
+const CONTAINER: &str = "META-INF/container.xml";
+
+/// Metadata read from the package document (OPF) of an EPUB
+///
+/// - identifiers: every `dc:identifier`, e.g. ISBN, UUID or URN
+/// - creators: every `dc:creator`, usually the authors
+/// - cover: path of the cover image inside the archive
+/// - series, series_index: the `calibre:series` metas
+/// - digest: hash of the package document, telling apart
+///   the books without identifier sharing a title
+///
+#[derive(Debug, Default)]
+pub struct Package {
+    pub identifiers: Vec<String>,
+    pub title: String,
+    pub creators: Vec<String>,
+    pub publisher: String,
+    pub date: String,
+    pub language: String,
+    pub description: String,
+    pub cover: Option<String>,
+    pub series: Option<String>,
+    pub series_index: Option<String>,
+    pub digest: u64,
+}
+
+/// Manifest entry of the package
+///
+struct Item {
+    href: String,
+    properties: String,
+}
+
+/// EPUB reading error
+///
+#[derive(Error, Debug)]
+pub enum EpubError {
+    #[error("Cannot read EPUB file: {0}")]
+    Unreadable(String),
+    #[error("Invalid EPUB archive: {0}")]
+    InvalidArchive(String),
+    #[error("Invalid EPUB package: {0}")]
+    InvalidPackage(String),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Package {
+    /// Read the package metadata from an EPUB file.
+    ///
+    /// The container file tells where the package document is,
+    /// then the package metadata are parsed.
+    ///
+    pub fn open(path: &Path) -> Result<Package, EpubError> {
+        let file = File::open(path).map_err(|e| EpubError::Unreadable(e.to_string()))?;
+        let mut archive = ZipArchive::new(file)
+            .map_err(|e| EpubError::InvalidArchive(e.to_string()))?;
+
+        let container = read_entry(&mut archive, CONTAINER)?;
+        let rootfile = find_rootfile(&container)?;
+        let opf = read_entry(&mut archive, &rootfile)?;
+
+        let base = match rootfile.rfind('/') {
+            Some(pos) => &rootfile[..=pos],
+            None => "",
+        };
+        let mut package = parse_package(&opf, base)?;
+        package.digest = fnv1a(&opf);
+        Ok(package)
+    }
+
+    /// Read the cover image bytes from the EPUB file.
+    ///
+    pub fn read_cover(&self, path: &Path) -> Result<Option<Vec<u8>>, EpubError> {
+        let cover = match &self.cover {
+            Some(cover) => cover,
+            None => return Ok(None),
+        };
+
+        let file = File::open(path).map_err(|e| EpubError::Unreadable(e.to_string()))?;
+        let mut archive = ZipArchive::new(file)
+            .map_err(|e| EpubError::InvalidArchive(e.to_string()))?;
+        let mut entry = archive
+            .by_name(cover)
+            .map_err(|e| EpubError::InvalidArchive(format!("{}: {}", cover, e)))?;
+        let mut buf = vec![];
+
+        entry
+            .read_to_end(&mut buf)
+            .map_err(|e| EpubError::Unreadable(e.to_string()))?;
+        Ok(Some(buf))
+    }
+
+    /// Map the package metadata to a library volume.
+    ///
+    /// The first valid ISBN identifies the volume. Without ISBN
+    /// a synthetic key is derived from the first identifier, or
+    /// from the title and the package document when the package
+    /// has no identifier.
+    ///
+    pub fn to_volume(&self) -> Volume {
+        let isbn = self.isbns().into_iter().next().unwrap_or_else(|| {
+            let seed = match self.identifiers.first() {
+                Some(identifier) => identifier.clone(),
+                None => format!("{}:{:016x}", self.title, self.digest),
+            };
+            Isbn::synthetic(&seed)
+        });
+
+        Volume {
+            isbn,
+            title: self.title.clone(),
+            description: self.description.clone(),
+            publisher: self.publisher.clone(),
+            published_date: self.date.split('T').next().unwrap_or_default().to_string(),
+            language: self.language.clone(),
+            authors: self.creators.clone(),
+            series: self.series.clone(),
+            series_index: self
+                .series_index
+                .as_ref()
+                .and_then(|index| index.split('.').next())
+                .and_then(|index| index.parse().ok()),
+            ..Volume::default()
+        }
+    }
+
+    /// The identifiers which are valid ISBN, with or
+    /// without the `urn:isbn:` prefix.
+    ///
+    pub fn isbns(&self) -> Vec<Isbn> {
+        self.identifiers
+            .iter()
+            .map(|id| {
+                let lower = id.trim().to_lowercase();
+                let value = lower
+                    .strip_prefix("urn:isbn:")
+                    .or_else(|| lower.strip_prefix("isbn:"))
+                    .unwrap_or(&lower);
+                Isbn::new(&value.replace(' ', "").to_uppercase())
+            })
+            .filter(|isbn| isbn.is_valid())
+            .collect()
+    }
+}
+
+/// Read an archive entry to string
+///
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<String, EpubError> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| EpubError::InvalidArchive(format!("{}: {}", name, e)))?;
+    let mut buf = String::new();
+
+    entry
+        .read_to_string(&mut buf)
+        .map_err(|e| EpubError::Unreadable(e.to_string()))?;
+    Ok(buf)
+}
+
+/// Returns the package document path from the container file
+///
+fn find_rootfile(container: &str) -> Result<String, EpubError> {
+    let mut reader = Reader::from_str(container);
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                if e.local_name().as_ref() == b"rootfile" =>
+            {
+                let path = e
+                    .try_get_attribute("full-path")
+                    .ok()
+                    .flatten()
+                    .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()));
+                if let Some(path) = path {
+                    return Ok(path);
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(EpubError::InvalidPackage(e.to_string())),
+            _ => (),
+        }
+    }
+
+    Err(EpubError::InvalidPackage("no rootfile in container".into()))
+}
+
+/// Parse the package document metadata.
+///
+/// The cover is looked up in the manifest, either by the EPUB 3
+/// `cover-image` property or by the EPUB 2 `cover` meta. Its
+/// path is resolved against the package document folder.
+///
+fn parse_package(opf: &str, base: &str) -> Result<Package, EpubError> {
+    let mut reader = Reader::from_str(opf);
+    reader.config_mut().trim_text(true);
+
+    let mut package = Package::default();
+    let mut current: Option<Vec<u8>> = None;
+    let mut items: HashMap<String, Item> = HashMap::new();
+    let mut cover_id: Option<String> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let name = e.local_name().as_ref().to_vec();
+                let attr = |key: &str| {
+                    e.try_get_attribute(key)
+                        .ok()
+                        .flatten()
+                        .and_then(|attr| attr.unescape_value().ok())
+                        .map(|value| value.into_owned())
+                };
+
+                match name.as_slice() {
+                    b"item" => {
+                        if let (Some(id), Some(href)) = (attr("id"), attr("href")) {
+                            let properties = attr("properties").unwrap_or_default();
+                            items.insert(id, Item { href, properties });
+                        }
+                    }
+                    b"meta" if attr("name").as_deref() == Some("cover") => {
+                        cover_id = attr("content");
+                    }
+                    b"meta" if attr("name").as_deref() == Some("calibre:series") => {
+                        package.series = attr("content").filter(|name| !name.is_empty());
+                    }
+                    b"meta"
+                        if attr("name").as_deref() == Some("calibre:series_index") =>
+                    {
+                        package.series_index = attr("content");
+                    }
+                    _ => (),
+                };
+                current = Some(name);
+            }
+            Ok(Event::End(_)) => current = None,
+            Ok(Event::Text(text)) => {
+                let value = text
+                    .unescape()
+                    .map_err(|e| EpubError::InvalidPackage(e.to_string()))?
+                    .into_owned();
+                match current.as_deref() {
+                    Some(b"identifier") => package.identifiers.push(value),
+                    Some(b"creator") => package.creators.push(value),
+                    Some(b"title") if package.title.is_empty() => package.title = value,
+                    Some(b"publisher") => package.publisher = value,
+                    Some(b"date") if package.date.is_empty() => package.date = value,
+                    Some(b"language") if package.language.is_empty() => {
+                        package.language = value
+                    }
+                    Some(b"description") => package.description = value,
+                    _ => (),
+                };
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(EpubError::InvalidPackage(e.to_string())),
+            _ => (),
+        }
+    }
+
+    let cover = items
+        .values()
+        .find(|item| item.properties.split(' ').any(|p| p == "cover-image"))
+        .or_else(|| cover_id.and_then(|id| items.get(&id)));
+    package.cover = cover.map(|item| format!("{}{}", base, item.href));
+
+    Ok(package)
+}
@@ -0,0 +1,22 @@
+use super::Isbn;
+
+/// Events emitted while new volumes are fetched
+///
+/// - Started: the lookups are starting, with the count of ISBN to fetch
+/// - Lookup: a single ISBN lookup is starting
+/// - Completed: a single ISBN lookup ended, successfully or not
+/// - Finished: all the lookups ended
+///
+#[derive(Debug)]
+pub enum FetchEvent<'a> {
+    Started { total: usize },
+    Lookup { isbn: &'a Isbn },
+    Completed { isbn: &'a Isbn, found: bool },
+    Finished,
+}
+
+/// Receives fetch events, e.g. to display the progress
+///
+pub trait FetchObserver {
+    fn notify(&self, event: &FetchEvent);
+}
@@ -0,0 +1,193 @@
+use crate::books::digest::fnv1a;
+use home_dir::HomeDirExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_file, rename, write};
+use std::io::Result as IoResult;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Response cache settings
+///
+/// - enabled: store and reuse the API responses
+/// - dir: folder of the cached responses
+/// - ttl: seconds a response is served without asking the API
+/// - offline: serve only cached responses, never calling the API
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub dir: String,
+    pub ttl: u64,
+    pub offline: bool,
+}
+
+/// On-disk cache of the API responses, one file per request
+/// url. Urls are stored without the API key.
+///
+#[derive(Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// A cached response body with the validator to revalidate it
+///
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    pub stored_at: u64,
+    pub body: Value,
+}
+
+/// Cache content summary
+///
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub expired: usize,
+    pub bytes: u64,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: "~/.books/cache".into(),
+            ttl: 7 * 24 * 3600,
+            offline: false,
+        }
+    }
+}
+
+impl ResponseCache {
+    /// Open the cache folder set in the config, expanding `~`.
+    /// The folder is created on the first write.
+    ///
+    pub fn new(config: &CacheConfig) -> ResponseCache {
+        let dir = PathBuf::from(&config.dir);
+        ResponseCache {
+            dir: dir.expand_home().unwrap_or(dir),
+            ttl: Duration::from_secs(config.ttl),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the response cached for the url, fresh or not.
+    ///
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        read_to_string(self.entry_path(url))
+            .ok()
+            .and_then(|data| serde_json::from_str::<CachedResponse>(&data).ok())
+            .filter(|entry| entry.url == url)
+    }
+
+    /// True if the response can be served without revalidation
+    ///
+    pub fn is_fresh(&self, entry: &CachedResponse) -> bool {
+        now().saturating_sub(entry.stored_at) < self.ttl.as_secs()
+    }
+
+    /// Store a response, replacing the previous one.
+    ///
+    pub fn put(&self, url: &str, etag: Option<String>, body: Value) -> IoResult<()> {
+        let entry = CachedResponse {
+            url: url.to_string(),
+            etag,
+            stored_at: now(),
+            body,
+        };
+        let path = self.entry_path(url);
+        let temp = path.with_extension("tmp");
+
+        create_dir_all(&self.dir)?;
+        write(&temp, serde_json::to_vec(&entry)?)?;
+        rename(&temp, &path)
+    }
+
+    /// Count the cached responses, the expired ones and their size.
+    ///
+    pub fn stats(&self) -> IoResult<CacheStats> {
+        let mut stats = CacheStats::default();
+
+        for path in self.entries()? {
+            stats.entries += 1;
+            stats.bytes += path.metadata().map(|m| m.len()).unwrap_or_default();
+            if !self.is_live(&path) {
+                stats.expired += 1;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Remove all the cached responses.
+    ///
+    /// Returns the count of the removed responses.
+    ///
+    pub fn clear(&self) -> IoResult<usize> {
+        let entries = self.entries()?;
+        for path in entries.iter() {
+            remove_file(path)?;
+        }
+        Ok(entries.len())
+    }
+
+    /// Remove the expired responses and the unreadable ones.
+    ///
+    /// Returns the count of the removed responses.
+    ///
+    pub fn prune(&self) -> IoResult<usize> {
+        let mut removed = 0;
+        for path in self.entries()? {
+            if !self.is_live(&path) {
+                remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(url)))
+    }
+
+    fn entries(&self) -> IoResult<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut entries = vec![];
+        for entry in read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                entries.push(path);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn is_live(&self, path: &Path) -> bool {
+        read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<CachedResponse>(&data).ok())
+            .is_some_and(|entry| self.is_fresh(&entry))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
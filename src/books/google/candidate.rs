@@ -0,0 +1,81 @@
+use super::volume::{create_volume, industry_isbns};
+use super::{Isbn, Volume};
+use serde_json::Value;
+use strsim::jaro_winkler;
+
+/// A search result proposed for a title and author lookup
+///
+/// - volume: the volume mapped from the search result; its ISBN is the
+///   first industry identifier or a synthetic key when there is none
+/// - score: similarity with the requested title and author, from 0 to 1
+///
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub volume: Volume,
+    pub score: f64,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Candidate {
+    /// Build a candidate from a search result item, scoring it
+    /// against the requested title and author.
+    ///
+    pub fn from_item(
+        item: &Value,
+        title: &str,
+        author: Option<&str>,
+    ) -> Option<Candidate> {
+        let id = item.get("id").and_then(|id| id.as_str())?;
+        let mut volume = create_volume(item)?;
+
+        volume.isbn = industry_isbns(item)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| Isbn::synthetic(id));
+
+        let score = score(&volume, title, author);
+        Some(Candidate { volume, score })
+    }
+
+    /// Publication year, when known
+    ///
+    pub fn year(&self) -> &str {
+        self.volume.published_date.get(..4).unwrap_or_default()
+    }
+}
+
+/// Title similarity, averaged with the best author similarity
+/// when an author is requested. Titles weigh more than authors.
+///
+fn score(volume: &Volume, title: &str, author: Option<&str>) -> f64 {
+    let title_score = similarity(title, &volume.title);
+
+    match author {
+        Some(author) => {
+            let author_score = volume
+                .authors
+                .iter()
+                .map(|name| similarity(author, name))
+                .fold(0.0, f64::max);
+            title_score * 0.7 + author_score * 0.3
+        }
+        None => title_score,
+    }
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    jaro_winkler(&normalize(a), &normalize(b))
+}
+
+/// Lowercase words without punctuation
+///
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
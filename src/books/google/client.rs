@@ -1,48 +1,239 @@
 use futures::stream::{self, StreamExt};
 
-use super::get_volume;
-use super::search_isbn;
-use super::{Isbn, Volume};
+use super::{build_http_client, create_volume, get_volume, HttpConfig, HttpError};
+use super::{search_isbn, search_title, Candidate};
+use super::{CacheConfig, Recorder, ResponseCache, Transport};
+use super::{FetchError, FetchEvent, FetchObserver, Isbn, Volume};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use std::{thread, time};
+use tracing::field::Empty;
+use tracing::{debug, info, info_span, warn, Instrument, Span};
 
+/// Fields the search projection can leave out while the volume
+/// resource holds them. A description, image or page count missing
+/// from the search result is missing from the volume as well.
+///
+const VOLUME_ONLY_FIELDS: [&str; 3] = ["title", "publisher", "published_date"];
+
+/// Client configuration
+///
+/// - base_uri: Google Books API base url
+/// - api_key: API key, empty for keyless access
+/// - country: ISO 3166-1 country code used to resolve availability
+/// - lang_restrict: restricts search results to an ISO 639-1 language
+/// - print_type: restricts search results to `all`, `books` or `magazines`
+/// - on_mismatch: what to do when no search result lists the requested ISBN
+/// - http: settings of the HTTP client
+/// - cache: settings of the response cache
+/// - record: folder where the responses are saved as test fixtures
+///
 #[derive(Debug)]
 pub struct ApiConfig {
     pub base_uri: String,
     pub api_key: String,
+    pub country: Option<String>,
+    pub lang_restrict: Option<String>,
+    pub print_type: Option<String>,
+    pub on_mismatch: MismatchPolicy,
+    pub http: HttpConfig,
+    pub cache: CacheConfig,
+    pub record: Option<String>,
+}
+
+/// Handling of the search results not matching the requested ISBN
+///
+/// - Flag: keep the first result, marked as a low confidence match
+/// - Reject: fail the lookup
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MismatchPolicy {
+    #[default]
+    Flag,
+    Reject,
 }
 
 #[derive(Debug)]
 pub struct Client {
     config: ApiConfig,
+    transport: Transport,
+}
+
+/// Result of a single ISBN lookup
+///
+/// - elapsed: the lookup duration
+/// - requests: the HTTP requests sent, cached responses excluded
+/// - from_search: the volume was built from the search result
+///   alone, without asking the volume endpoint
+///
+#[derive(Debug)]
+pub struct Lookup {
+    pub isbn: Isbn,
+    pub result: Result<Volume, FetchError>,
+    pub elapsed: Duration,
+    pub requests: usize,
+    pub from_search: bool,
 }
 
 impl Client {
-    pub fn new(config: ApiConfig) -> Client {
-        Client { config }
+    /// Create the client with the HTTP client it shares
+    /// among all its requests and the response cache.
+    ///
+    /// The cache is bypassed while recording, so every
+    /// response comes from the API.
+    ///
+    pub fn new(config: ApiConfig) -> Result<Client, HttpError> {
+        let http = build_http_client(&config.http)?;
+        let cache = match config.cache.enabled && config.record.is_none() {
+            true => Some(ResponseCache::new(&config.cache)),
+            false => None,
+        };
+        let recorder = config
+            .record
+            .as_ref()
+            .map(|dir| Recorder::new(dir, &config.base_uri));
+        let read_timeout = Duration::from_secs(config.http.read_timeout);
+        let transport =
+            Transport::new(http, read_timeout, cache, config.cache.offline, recorder);
+
+        Ok(Client { config, transport })
     }
 
-    pub async fn search_book(&self, isbn: &Isbn) -> Option<Volume> {
-        match search_isbn(&isbn.to_string(), &self.config).await {
-            Some(id) => get_volume(&id, &self.config).await.map(|mut volume| {
-                volume.isbn = isbn.clone();
-                volume
-            }),
-            None => None,
+    /// Search a single book by ISBN.
+    ///
+    /// Every lookup runs in its own span carrying the ISBN,
+    /// the lookup status and the elapsed time.
+    ///
+    pub async fn search_book(&self, isbn: &Isbn) -> Lookup {
+        let span = info_span!("lookup", isbn = %isbn, status = Empty, elapsed_ms = Empty);
+
+        async move {
+            let start = Instant::now();
+            let sent = self.transport.sent();
+            let mut from_search = true;
+            let result = self.resolve(isbn, &mut from_search).await;
+            let elapsed = start.elapsed();
+
+            let span = Span::current();
+            span.record("status", lookup_status(&result));
+            span.record("elapsed_ms", elapsed.as_millis() as u64);
+            info!("lookup completed");
+
+            Lookup {
+                isbn: isbn.clone(),
+                result,
+                elapsed,
+                requests: self.transport.sent() - sent,
+                from_search,
+            }
         }
+        .instrument(span)
+        .await
     }
 
-    pub async fn search_books(&self, list: Vec<Isbn>) -> Vec<Volume> {
+    /// Search a list of books one after the other.
+    ///
+    /// The observer is notified when every lookup starts and ends.
+    ///
+    pub async fn search_books(
+        &self,
+        list: Vec<Isbn>,
+        observer: Option<&dyn FetchObserver>,
+    ) -> Vec<Lookup> {
+        let notify = |event: FetchEvent| {
+            if let Some(observer) = observer {
+                observer.notify(&event);
+            }
+        };
+
         let stream = stream::iter(list.iter()).then(|isbn| {
             wait(500);
-            self.search_book(isbn)
+            notify(FetchEvent::Lookup { isbn });
+            async move {
+                let lookup = self.search_book(isbn).await;
+                notify(FetchEvent::Completed {
+                    isbn,
+                    found: lookup.result.is_ok(),
+                });
+                lookup
+            }
         });
 
-        stream
-            .collect::<Vec<_>>()
-            .await
+        stream.collect::<Vec<_>>().await
+    }
+
+    /// Search books by title and author, for the books without ISBN.
+    ///
+    /// Returns the candidates ranked by similarity with the
+    /// requested title and author, best first.
+    ///
+    pub async fn search_candidates(
+        &self,
+        title: &str,
+        author: Option<&str>,
+    ) -> Result<Vec<Candidate>, FetchError> {
+        let items = search_title(title, author, &self.config, &self.transport).await?;
+        let mut candidates: Vec<Candidate> = items
+            .iter()
+            .filter_map(|item| Candidate::from_item(item, title, author))
+            .collect();
+
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        Ok(candidates)
+    }
+
+    /// Build the volume from the search result. The volume
+    /// endpoint is requested only when the result lacks some
+    /// of the fields the search can omit, to fill them.
+    ///
+    async fn resolve(
+        &self,
+        isbn: &Isbn,
+        from_search: &mut bool,
+    ) -> Result<Volume, FetchError> {
+        let hit = search_isbn(isbn, &self.config, &self.transport).await?;
+        if !hit.verified && self.rejects_mismatch() {
+            return Err(FetchError::Mismatch);
+        }
+
+        let mut volume = create_volume(&hit.item).unwrap_or_default();
+        let missing: Vec<&str> = volume
+            .missing_fields()
             .into_iter()
-            .flatten()
-            .collect()
+            .filter(|field| VOLUME_ONLY_FIELDS.contains(field))
+            .collect();
+        if !missing.is_empty() {
+            debug!(missing = ?missing, "search result incomplete");
+            *from_search = false;
+            match get_volume(&hit.id, &self.config, &self.transport).await {
+                Ok(full) => volume.fill_from(&full),
+                Err(e) if volume.title.is_empty() => return Err(e),
+                Err(e) => warn!(error = %e, "keeping the incomplete search result"),
+            }
+        }
+
+        if !hit.verified {
+            warn!("search result does not match the ISBN");
+            volume.low_confidence = true;
+        }
+        volume.isbn = isbn.clone();
+
+        Ok(volume)
+    }
+
+    fn rejects_mismatch(&self) -> bool {
+        self.config.on_mismatch == MismatchPolicy::Reject
+    }
+}
+
+fn lookup_status(result: &Result<Volume, FetchError>) -> &'static str {
+    match result {
+        Ok(volume) if volume.low_confidence => "low_confidence",
+        Ok(_) => "found",
+        Err(FetchError::NotFound) => "not_found",
+        Err(FetchError::Mismatch) => "mismatch",
+        Err(_) => "failed",
     }
 }
 
@@ -0,0 +1,44 @@
+use thiserror::Error;
+
+/// Lookup error, tells why a volume could not be fetched.
+///
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FetchError {
+    #[error("Cannot build the request url")]
+    InvalidUrl,
+    #[error("Request failed: {0}")]
+    Network(String),
+    #[error("Unexpected HTTP status {0}")]
+    Status(u16),
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+    #[error("No volume found")]
+    NotFound,
+    #[error("No search result matches the ISBN")]
+    Mismatch,
+    #[error("Not in the cache, offline mode")]
+    Offline,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl FetchError {
+    /// The API refused the credentials
+    ///
+    pub fn is_auth(&self) -> bool {
+        matches!(self, FetchError::Status(401) | FetchError::Status(403))
+    }
+
+    /// The API could not be reached or could not serve the
+    /// request: network failures, quota exceeded, server errors
+    ///
+    pub fn is_network(&self) -> bool {
+        match self {
+            FetchError::Network(_) => true,
+            FetchError::Status(status) => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
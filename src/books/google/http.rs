@@ -0,0 +1,142 @@
+use reqwest::{Certificate, Client as HttpClient, Proxy};
+use serde::{Deserialize, Serialize};
+use std::fs::read;
+use std::time::Duration;
+use thiserror::Error;
+
+/// HTTP client settings
+///
+/// - connect_timeout: seconds allowed to open the connection
+/// - read_timeout: seconds allowed without data from the server, waiting
+///   for the response or between two chunks of its body
+/// - timeout: seconds allowed to the whole request, response body included
+/// - proxy: proxy url used for every request
+/// - ca_certificates: paths of PEM files with extra root certificates,
+///   each one holding a single certificate or a bundle
+/// - user_agent: User-Agent header sent with every request
+/// - gzip: accept gzip compressed responses
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    pub connect_timeout: u64,
+    pub read_timeout: u64,
+    pub timeout: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ca_certificates: Vec<String>,
+    pub user_agent: String,
+    pub gzip: bool,
+}
+
+/// Error building the HTTP client from its settings
+///
+#[derive(Error, Debug)]
+pub enum HttpError {
+    #[error("Invalid proxy \"{0}\": {1}")]
+    InvalidProxy(String, String),
+    #[error("Cannot read certificate \"{0}\": {1}")]
+    InvalidCertificate(String, String),
+    #[error("Cannot build the HTTP client: {0}")]
+    Unavailable(String),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: 10,
+            read_timeout: 15,
+            timeout: 30,
+            proxy: None,
+            ca_certificates: vec![],
+            user_agent: format!("books/{}", env!("CARGO_PKG_VERSION")),
+            gzip: true,
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Build the HTTP client shared by all the API requests,
+/// so connections are reused across lookups.
+///
+pub fn build_http_client(config: &HttpConfig) -> Result<HttpClient, HttpError> {
+    let mut builder = HttpClient::builder()
+        .connect_timeout(Duration::from_secs(config.connect_timeout))
+        .timeout(Duration::from_secs(config.timeout))
+        .user_agent(config.user_agent.as_str())
+        .gzip(config.gzip);
+
+    if let Some(url) = &config.proxy {
+        let proxy = Proxy::all(url.as_str())
+            .map_err(|e| HttpError::InvalidProxy(url.clone(), e.to_string()))?;
+        builder = builder.proxy(proxy);
+    }
+
+    for path in config.ca_certificates.iter() {
+        for certificate in read_certificates(path)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| HttpError::Unavailable(e.to_string()))
+}
+
+/// Read all the certificates of a PEM file, which can be
+/// a single certificate or a bundle of them.
+///
+fn read_certificates(path: &str) -> Result<Vec<Certificate>, HttpError> {
+    read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|pem| Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string()))
+        .and_then(|certificates| match certificates.is_empty() {
+            true => Err("no certificate found".to_string()),
+            false => Ok(certificates),
+        })
+        .map_err(|e| HttpError::InvalidCertificate(path.to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(name: &str) -> String {
+        format!("{}/tests/certs/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn reads_every_certificate_of_a_bundle() {
+        let certificates = read_certificates(&cert("bundle.pem")).unwrap();
+
+        assert_eq!(certificates.len(), 2);
+    }
+
+    #[test]
+    fn rejects_a_file_without_certificates() {
+        let path = format!("{}/Cargo.toml", env!("CARGO_MANIFEST_DIR"));
+
+        assert!(matches!(
+            read_certificates(&path),
+            Err(HttpError::InvalidCertificate(..))
+        ));
+    }
+
+    #[test]
+    fn builds_the_client_with_a_bundle() {
+        let config = HttpConfig {
+            ca_certificates: vec![cert("bundle.pem")],
+            ..HttpConfig::default()
+        };
+
+        assert!(build_http_client(&config).is_ok());
+    }
+}
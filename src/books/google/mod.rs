@@ -1,8 +1,21 @@
+mod cache;
+mod candidate;
 mod client;
+mod error;
+mod http;
+mod recorder;
+mod request;
 mod search;
+mod transport;
 mod volume;
 
-pub use super::{Isbn, Volume};
-pub use client::{ApiConfig, Client};
-pub use search::search_isbn;
-pub use volume::get_volume;
+pub use super::{FetchEvent, FetchObserver, Isbn, Volume};
+pub use cache::{CacheConfig, CacheStats, ResponseCache};
+pub use candidate::Candidate;
+pub use client::{ApiConfig, Client, MismatchPolicy};
+pub use error::FetchError;
+pub use http::{build_http_client, HttpConfig, HttpError};
+pub use recorder::Recorder;
+pub use search::{search_isbn, search_title};
+pub use transport::Transport;
+pub use volume::{create_volume, get_volume};
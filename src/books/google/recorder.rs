@@ -0,0 +1,108 @@
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{create_dir_all, write};
+use std::io::Result as IoResult;
+use std::path::PathBuf;
+
+/// A recorded API response, replayed by the test mock server
+///
+/// - url: the request path and query, relative to the API base url,
+///   without the API key
+/// - status: the HTTP status
+/// - body: the response document, when it is valid JSON
+/// - raw: the response text, when it is not
+///
+#[derive(Debug, Serialize)]
+pub struct Fixture {
+    pub url: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+}
+
+/// Writes every API response received as a fixture file
+///
+#[derive(Debug)]
+pub struct Recorder {
+    dir: PathBuf,
+    base_uri: String,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Fixture {
+    /// Build the fixture of a response, dropping the API key
+    /// and the base url from the request url.
+    ///
+    pub fn new(url: &Url, base_uri: &str, status: u16, text: &str) -> Fixture {
+        let mut relative = url.clone();
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(name, _)| name != "key")
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        relative.set_query(None);
+        if !pairs.is_empty() {
+            relative.query_pairs_mut().extend_pairs(pairs);
+        }
+
+        let relative = relative.to_string();
+        let url = relative
+            .strip_prefix(base_uri)
+            .unwrap_or(&relative)
+            .to_string();
+
+        match serde_json::from_str::<Value>(text) {
+            Ok(body) => Fixture {
+                url,
+                status,
+                body: Some(body),
+                raw: None,
+            },
+            Err(_) => Fixture {
+                url,
+                status,
+                body: None,
+                raw: Some(text.to_string()),
+            },
+        }
+    }
+
+    /// File name derived from the url, readable and stable
+    ///
+    pub fn file_name(&self) -> String {
+        let slug: String = self
+            .url
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let slug: Vec<&str> = slug.split('-').filter(|s| !s.is_empty()).collect();
+
+        format!("{}.json", slug.join("-"))
+    }
+}
+
+impl Recorder {
+    pub fn new(dir: &str, base_uri: &str) -> Recorder {
+        Recorder {
+            dir: PathBuf::from(dir),
+            base_uri: base_uri.to_string(),
+        }
+    }
+
+    /// Save the response as a fixture, replacing the
+    /// one recorded before for the same url.
+    ///
+    pub fn record(&self, url: &Url, status: u16, text: &str) -> IoResult<()> {
+        let fixture = Fixture::new(url, &self.base_uri, status, text);
+        let path = self.dir.join(fixture.file_name());
+
+        create_dir_all(&self.dir)?;
+        write(path, serde_json::to_string_pretty(&fixture)?)
+    }
+}
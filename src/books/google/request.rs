@@ -0,0 +1,86 @@
+use super::ApiConfig;
+use reqwest::Url;
+
+/// Build an API url appending the endpoint path segments to the base uri.
+///
+/// Path segments and query parameters are url-encoded. The API key is appended only
+/// when configured: Google Books answers keyless requests too,
+/// just with a lower quota.
+///
+pub fn build_url(
+    path: &[&str],
+    query: &[(&str, &str)],
+    params: &ApiConfig,
+) -> Option<Url> {
+    let mut url = Url::parse(&params.base_uri).ok()?;
+
+    {
+        let mut segments = url.path_segments_mut().ok()?;
+        segments.pop_if_empty().extend(path);
+    }
+    {
+        let mut pairs = url.query_pairs_mut();
+        for (name, value) in query.iter() {
+            pairs.append_pair(name, value);
+        }
+        if let Some(country) = &params.country {
+            pairs.append_pair("country", country);
+        }
+        if !params.api_key.is_empty() {
+            pairs.append_pair("key", &params.api_key);
+        }
+    }
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+
+    Some(url)
+}
+
+/// Printable version of an API url with the key masked,
+/// safe to be written in the logs.
+///
+pub fn redact(url: &Url) -> String {
+    let mut redacted = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| match name.as_ref() {
+            "key" => (name.into_owned(), "REDACTED".to_string()),
+            _ => (name.into_owned(), value.into_owned()),
+        })
+        .collect();
+
+    if !pairs.is_empty() {
+        redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    redacted.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_masks_the_key_and_keeps_the_other_parameters() {
+        let url = Url::parse(
+            "https://www.googleapis.com/books/v1/volumes?q=isbn%3A9780262033848&key=SECRET&country=IT",
+        )
+        .unwrap();
+
+        let redacted = redact(&url);
+
+        assert_eq!(
+            redacted,
+            "https://www.googleapis.com/books/v1/volumes?q=isbn%3A9780262033848&key=REDACTED&country=IT"
+        );
+    }
+
+    #[test]
+    fn redact_leaves_urls_without_key_unchanged() {
+        let url = Url::parse("https://www.googleapis.com/books/v1/volumes/xMBrDwAAQBAJ")
+            .unwrap();
+
+        assert_eq!(redact(&url), url.to_string());
+    }
+}
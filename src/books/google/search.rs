@@ -1,46 +1,120 @@
-use super::ApiConfig;
-use reqwest::get;
+use super::request::build_url;
+use super::volume::industry_isbns;
+use super::{ApiConfig, FetchError, Isbn, Transport};
+use reqwest::Url;
 use serde_json::Value;
 
-fn get_url(isbn: &str, params: &ApiConfig) -> String {
-    format!(
-        "{base}volumes/?projection=full&key={key}&q=isbn:{isbn}",
-        base = params.base_uri,
-        key = params.api_key,
-        isbn = isbn
-    )
+/// Candidates requested by a title search
+///
+const MAX_CANDIDATES: &str = "10";
+
+/// The search result chosen for an ISBN
+///
+/// - id: the volume id
+/// - item: the search result, with the full volume info
+/// - verified: true if the result lists the requested ISBN
+///   among its industry identifiers
+///
+#[derive(Debug)]
+pub struct Hit {
+    pub id: String,
+    pub item: Value,
+    pub verified: bool,
+}
+
+fn get_url(query: &str, extra: &[(&str, &str)], params: &ApiConfig) -> Option<Url> {
+    let mut pairs = vec![("projection", "full"), ("q", query)];
+    pairs.extend_from_slice(extra);
+
+    if let Some(lang) = &params.lang_restrict {
+        pairs.push(("langRestrict", lang));
+    }
+    if let Some(print_type) = &params.print_type {
+        pairs.push(("printType", print_type));
+    }
+
+    build_url(&["volumes"], &pairs, params)
+}
+
+/// Search a volume by ISBN.
+///
+/// The first result listing the ISBN among its industry identifiers
+/// is chosen. When none does, the first result is returned unverified.
+///
+pub async fn search_isbn(
+    isbn: &Isbn,
+    params: &ApiConfig,
+    transport: &Transport,
+) -> Result<Hit, FetchError> {
+    let query = format!("isbn:{}", isbn);
+    let url = get_url(&query, &[], params).ok_or(FetchError::InvalidUrl)?;
+
+    transport.get_json(url, "search").await.and_then(|data| {
+        choose_result(&get_items(data), isbn).ok_or(FetchError::NotFound)
+    })
 }
 
-pub async fn search_isbn(isbn: &str, params: &ApiConfig) -> Option<String> {
-    let url: String = get_url(isbn, params);
-
-    match get(&url).await {
-        Ok(response) => {
-            println!("Search: {:?}", response.status());
-            match response.status().as_u16() {
-                200 => match response.json::<Value>().await {
-                    Ok(data) => get_first_result(data),
-                    Err(_) => None,
-                },
-                _ => None,
-            }
-        }
-        Err(e) => {
-            println!("{:?}", e);
-            None
-        }
+/// Search volumes by title and, optionally, by author.
+///
+/// Returns the search result items as they are.
+///
+pub async fn search_title(
+    title: &str,
+    author: Option<&str>,
+    params: &ApiConfig,
+    transport: &Transport,
+) -> Result<Vec<Value>, FetchError> {
+    let mut query = format!("intitle:{}", phrase(title));
+    if let Some(author) = author {
+        query.push_str(&format!(" inauthor:{}", phrase(author)));
+    }
+
+    let extra = [("maxResults", MAX_CANDIDATES)];
+    let url = get_url(&query, &extra, params).ok_or(FetchError::InvalidUrl)?;
+
+    transport.get_json(url, "search").await.map(get_items)
+}
+
+/// Quote a phrase so the search qualifier applies to all its words
+///
+fn phrase(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', " ").trim())
+}
+
+fn get_items(data: Value) -> Vec<Value> {
+    match data {
+        Value::Object(mut map) => match map.remove("items") {
+            Some(Value::Array(items)) => items,
+            _ => vec![],
+        },
+        _ => vec![],
     }
 }
 
-fn get_first_result(data: Value) -> Option<String> {
-    data.get("items")
-        .and_then(|items| items.as_array())
-        .and_then(|list| {
-            list.get(0).map(|item| {
-                item.get("id")
-                    .and_then(|value| value.as_str())
-                    .map(|value| value.to_string())
-                    .unwrap_or_default()
-            })
-        })
+fn choose_result(items: &[Value], isbn: &Isbn) -> Option<Hit> {
+    let id = |item: &Value| {
+        item.get("id")
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    };
+
+    let matching = items.iter().find(|item| {
+        industry_isbns(item)
+            .iter()
+            .any(|candidate| candidate.matches(isbn))
+    });
+
+    match matching {
+        Some(item) => Some(Hit {
+            id: id(item),
+            item: item.clone(),
+            verified: true,
+        }),
+        None => items.first().map(|item| Hit {
+            id: id(item),
+            item: item.clone(),
+            verified: false,
+        }),
+    }
 }
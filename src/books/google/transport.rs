@@ -0,0 +1,180 @@
+use super::request::redact;
+use super::{FetchError, Recorder, ResponseCache};
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::{Client as HttpClient, Response, Url};
+use serde_json::Value;
+use std::cell::Cell;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing::{debug, warn};
+
+/// Sends the API requests through the shared HTTP client,
+/// serving and storing the responses in the cache when set.
+/// When recording, every response received is saved as a fixture.
+///
+/// The read timeout bounds every wait for data from the server:
+/// the response head, then each chunk of the body.
+///
+#[derive(Debug)]
+pub struct Transport {
+    http: HttpClient,
+    read_timeout: Duration,
+    cache: Option<ResponseCache>,
+    offline: bool,
+    recorder: Option<Recorder>,
+    sent: Cell<usize>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Transport {
+    pub fn new(
+        http: HttpClient,
+        read_timeout: Duration,
+        cache: Option<ResponseCache>,
+        offline: bool,
+        recorder: Option<Recorder>,
+    ) -> Transport {
+        Transport {
+            http,
+            read_timeout,
+            cache,
+            offline,
+            recorder,
+            sent: Cell::new(0),
+        }
+    }
+
+    /// Count of the requests sent to the API so far
+    ///
+    pub fn sent(&self) -> usize {
+        self.sent.get()
+    }
+
+    /// Get the JSON document at the url.
+    ///
+    /// A fresh cached response is served right away. A stale one
+    /// is revalidated with its ETag, when it has one. In offline
+    /// mode cached responses are served even if stale, and the
+    /// API is never called.
+    ///
+    /// The label names the request in the logs.
+    ///
+    pub async fn get_json(&self, url: Url, label: &str) -> Result<Value, FetchError> {
+        let key = redact(&url);
+        let cached = self.cache.as_ref().and_then(|cache| cache.get(&key));
+
+        if let Some(entry) = &cached {
+            let fresh = self.cache.as_ref().is_some_and(|c| c.is_fresh(entry));
+            if fresh || self.offline {
+                debug!(url = %key, fresh, "{} served from cache", label);
+                return Ok(entry.body.clone());
+            }
+        }
+        if self.offline {
+            debug!(url = %key, "{} not cached", label);
+            return Err(FetchError::Offline);
+        }
+
+        debug!(url = %key, "{} request", label);
+        let mut request = self.http.get(url.clone());
+        if let Some(etag) = cached.as_ref().and_then(|entry| entry.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        self.sent.set(self.sent.get() + 1);
+        let response = match timeout(self.read_timeout, request.send()).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => {
+                let e = e.without_url();
+                warn!(error = %e, "{} request failed", label);
+                return Err(FetchError::Network(e.to_string()));
+            }
+            Err(_) => return Err(self.timed_out(label)),
+        };
+
+        let status = response.status().as_u16();
+        debug!(status, "{} response", label);
+        match (status, cached) {
+            (304, Some(entry)) => {
+                self.store(&key, entry.etag, &entry.body);
+                Ok(entry.body)
+            }
+            (200, _) => {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.to_string());
+
+                let text = self.read_text(&url, response, label).await?;
+                match serde_json::from_str::<Value>(&text) {
+                    Ok(data) => {
+                        self.store(&key, etag, &data);
+                        Ok(data)
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "invalid {} response", label);
+                        Err(FetchError::InvalidResponse(e.to_string()))
+                    }
+                }
+            }
+            (status, _) => {
+                warn!(status, "{} failed", label);
+                let _ = self.read_text(&url, response, label).await;
+                Err(FetchError::Status(status))
+            }
+        }
+    }
+
+    // Reads the response body, recording it when requested
+    //
+    async fn read_text(
+        &self,
+        url: &Url,
+        mut response: Response,
+        label: &str,
+    ) -> Result<String, FetchError> {
+        let status = response.status().as_u16();
+        let mut body = vec![];
+        loop {
+            match timeout(self.read_timeout, response.chunk()).await {
+                Ok(Ok(Some(chunk))) => body.extend_from_slice(&chunk),
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => {
+                    let e = e.without_url();
+                    warn!(error = %e, "invalid {} response", label);
+                    return Err(FetchError::InvalidResponse(e.to_string()));
+                }
+                Err(_) => return Err(self.timed_out(label)),
+            }
+        }
+        let text = String::from_utf8_lossy(&body).into_owned();
+
+        if let Some(recorder) = &self.recorder {
+            if let Err(e) = recorder.record(url, status, &text) {
+                warn!(error = %e, "cannot record the {} response", label);
+            }
+        }
+        Ok(text)
+    }
+
+    fn timed_out(&self, label: &str) -> FetchError {
+        let seconds = self.read_timeout.as_secs();
+        warn!(seconds, "{} response timed out", label);
+        FetchError::Network(format!("no data received within {}s", seconds))
+    }
+
+    // A cache write failure only costs a later request,
+    // so it is logged and ignored.
+    //
+    fn store(&self, key: &str, etag: Option<String>, body: &Value) {
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(key, etag, body.clone()) {
+                warn!(error = %e, "cannot write the response cache");
+            }
+        }
+    }
+}
@@ -1,38 +1,35 @@
-use super::{ApiConfig, Volume};
-use reqwest::get;
+use super::request::build_url;
+use super::{ApiConfig, FetchError, Isbn, Transport, Volume};
+use crate::books::series::{number, parse_series};
+use reqwest::Url;
 use serde_json::Value;
 
-fn get_url(volume_id: &str, params: &ApiConfig) -> String {
-    format!(
-        "{base}volumes/{id}?key={key}",
-        base = params.base_uri,
-        key = params.api_key,
-        id = volume_id
-    )
+fn get_url(volume_id: &str, params: &ApiConfig) -> Option<Url> {
+    build_url(&["volumes", volume_id], &[], params)
 }
 
-pub async fn get_volume(volume_id: &str, params: &ApiConfig) -> Option<Volume> {
-    let url: String = get_url(volume_id, params);
-    match get(&url).await {
-        Ok(response) => {
-            println!("Volume: {:?}", response.status());
-            match response.status().as_u16() {
-                200 => match response.json::<Value>().await {
-                    Ok(data) => create_volume(data),
-                    Err(_) => None,
-                },
-                _ => None,
-            }
-        }
-        Err(e) => {
-            println!("{:?}", e);
-            None
-        }
-    }
+pub async fn get_volume(
+    volume_id: &str,
+    params: &ApiConfig,
+    transport: &Transport,
+) -> Result<Volume, FetchError> {
+    let url = get_url(volume_id, params).ok_or(FetchError::InvalidUrl)?;
+
+    transport
+        .get_json(url, "volume")
+        .await
+        .and_then(|data| create_volume(&data).ok_or(FetchError::NotFound))
 }
 
-fn create_volume(data: Value) -> Option<Volume> {
+/// Map a volume resource, or a search result item, to a Volume.
+///
+/// The series is read from the title and the subtitle. The series
+/// info of the provider gives only the position, its series id
+/// being no name.
+///
+pub fn create_volume(data: &Value) -> Option<Volume> {
     let mut volume = Volume::default();
+    let mut subtitle = String::new();
     let _ = data
         .get("volumeInfo")
         .and_then(|info| info.as_object())
@@ -40,30 +37,81 @@ fn create_volume(data: Value) -> Option<Volume> {
             for (k, vs) in info.iter() {
                 match k.as_ref() {
                     "title" => volume.title = as_string(vs),
+                    "subtitle" => subtitle = as_string(vs),
                     "description" => volume.description = as_string(vs),
                     "publisher" => volume.publisher = as_string(vs),
                     "publishedDate" => volume.published_date = as_string(vs),
                     "language" => volume.language = as_string(vs),
                     "pageCount" => volume.pages = as_i64(vs),
                     "imageLinks" => {
-                        volume.image = vs
-                            .get("thumbnail")
-                            .map(|thumb| as_string(thumb))
-                            .unwrap_or_default()
+                        volume.image =
+                            vs.get("thumbnail").map(as_string).unwrap_or_default()
                     }
+                    "canonicalVolumeLink" => volume.work_id = work_id(vs),
                     "authors" => {
                         volume.authors = vs
                             .as_array()
-                            .map(|list| list.iter().map(|item| as_string(item)).collect())
+                            .map(|list| list.iter().map(as_string).collect())
                             .unwrap_or_default()
                     }
                     _ => (),
                 };
             }
         });
+
+    let series = data.pointer("/volumeInfo/seriesInfo");
+    if let Some(found) = parse_series(&volume.title, &subtitle) {
+        volume.series = found.name;
+        volume.series_index = Some(found.index);
+    }
+    if let Some(series) = series {
+        if volume.series_index.is_none() {
+            volume.series_index = series
+                .get("bookDisplayNumber")
+                .and_then(|display| display.as_str())
+                .and_then(number);
+        }
+    }
     Some(volume)
 }
 
+/// Returns the valid ISBN listed in the volume industry
+/// identifiers, ISBN-13 first.
+///
+pub fn industry_isbns(data: &Value) -> Vec<Isbn> {
+    let identifiers = data
+        .pointer("/volumeInfo/industryIdentifiers")
+        .and_then(|ids| ids.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let of_type = |kind: &str| {
+        identifiers
+            .iter()
+            .filter(|id| id.get("type").and_then(|t| t.as_str()) == Some(kind))
+            .filter_map(|id| id.get("identifier").map(as_string))
+            .map(|value| Isbn::new(&value))
+            .filter(|isbn| isbn.is_valid())
+            .collect::<Vec<Isbn>>()
+    };
+
+    let mut isbns = of_type("ISBN_13");
+    isbns.extend(of_type("ISBN_10"));
+    isbns
+}
+
+/// Id of the canonical volume, shared by the editions the
+/// provider knows to be the same work
+///
+fn work_id(link: &Value) -> Option<String> {
+    let url = Url::parse(link.as_str()?).ok()?;
+    let id = url
+        .query_pairs()
+        .find(|(key, _)| key == "id")
+        .map(|(_, id)| id.into_owned());
+    id.filter(|id| !id.is_empty())
+}
+
 fn as_string(v: &Value) -> String {
     v.as_str().map_or(String::new(), |v| v.to_string())
 }
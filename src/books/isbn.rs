@@ -1,4 +1,6 @@
+use super::digest::fnv1a;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Book Isbn
 ///
@@ -16,10 +18,113 @@ impl Isbn {
         let value = str::replace(raw, "-", "");
         Isbn { value }
     }
+
+    /// Synthetic key for the books without an ISBN, derived
+    /// from a stable seed such as a package identifier.
+    ///
+    pub fn synthetic(seed: &str) -> Isbn {
+        Isbn {
+            value: format!("X{:016x}", fnv1a(seed)),
+        }
+    }
+
+    /// True if the value is a synthetic key and not a real ISBN
+    ///
+    pub fn is_synthetic(&self) -> bool {
+        self.value.starts_with('X')
+    }
+
+    /// True if the value is an ISBN-10 or an ISBN-13
+    /// with a valid check digit.
+    ///
+    pub fn is_valid(&self) -> bool {
+        match self.value.len() {
+            10 => is_valid_isbn10(&self.value),
+            13 => is_valid_isbn13(&self.value),
+            _ => false,
+        }
+    }
+
+    /// True if the value is a valid EAN-13 in the Bookland
+    /// prefixes (978 and 979) reserved to books.
+    ///
+    pub fn is_bookland(&self) -> bool {
+        (self.value.starts_with("978") || self.value.starts_with("979"))
+            && self.value.len() == 13
+            && is_valid_isbn13(&self.value)
+    }
+
+    /// The ISBN-13 form of a valid ISBN, converting the
+    /// ISBN-10 to the 978 prefix.
+    ///
+    pub fn to_isbn13(&self) -> Option<Isbn> {
+        if !self.is_valid() {
+            return None;
+        }
+        if self.value.len() == 13 {
+            return Some(self.clone());
+        }
+
+        let body = format!("978{}", &self.value[..9]);
+        let sum: u32 = body
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .enumerate()
+            .map(|(i, d)| if i % 2 == 0 { d } else { d * 3 })
+            .sum();
+        let check = (10 - sum % 10) % 10;
+
+        Some(Isbn {
+            value: format!("{}{}", body, check),
+        })
+    }
+
+    /// True if both are valid and identify the same book,
+    /// whatever their ISBN-10 or ISBN-13 form.
+    ///
+    pub fn matches(&self, other: &Isbn) -> bool {
+        match (self.to_isbn13(), other.to_isbn13()) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// ISBN-10: nine digits and a check digit (or X), weighted
+/// from 10 to 1, must sum to a multiple of 11.
+///
+fn is_valid_isbn10(value: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in value.chars().enumerate() {
+        let digit = match (i, c) {
+            (9, 'X') | (9, 'x') => 10,
+            (_, c) => match c.to_digit(10) {
+                Some(d) => d,
+                None => return false,
+            },
+        };
+        sum += digit * (10 - i as u32);
+    }
+    sum % 11 == 0
+}
+
+/// ISBN-13: thirteen digits, alternately weighted 1 and 3,
+/// must sum to a multiple of 10.
+///
+fn is_valid_isbn13(value: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in value.chars().enumerate() {
+        let digit = match c.to_digit(10) {
+            Some(d) => d,
+            None => return false,
+        };
+        sum += if i % 2 == 0 { digit } else { digit * 3 };
+    }
+    sum % 10 == 0
 }
 
-impl ToString for Isbn {
-    fn to_string(&self) -> String {
-        self.value.clone()
+impl fmt::Display for Isbn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
     }
 }
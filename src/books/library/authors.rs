@@ -0,0 +1,86 @@
+use super::LibraryService;
+use crate::books::{AuthorName, Volume};
+use std::collections::BTreeMap;
+
+/// An author with the library books written
+///
+/// - name: the name surname first, e.g. "Tolkien, J. R. R."
+/// - volumes: the books, in library order
+///
+#[derive(Debug, Clone)]
+pub struct AuthorEntry {
+    pub name: String,
+    pub volumes: Vec<Volume>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LibraryService {
+    /// Make a spelling of an author name stand for another name,
+    /// e.g. a pen name for the real one. Spellings differing only
+    /// in case, accents, punctuation or order already match.
+    ///
+    pub fn set_alias(&mut self, alias: &str, name: &str) {
+        self.remove_alias(alias);
+        self.library
+            .aliases
+            .insert(alias.trim().to_string(), name.trim().to_string());
+    }
+
+    /// Forget an alias, matching its spelling by key.
+    ///
+    /// Returns false if there was no such alias.
+    ///
+    pub fn remove_alias(&mut self, alias: &str) -> bool {
+        let key = AuthorName::parse(alias).key();
+        let before = self.library.aliases.len();
+        self.library
+            .aliases
+            .retain(|alias, _| AuthorName::parse(alias).key() != key);
+        self.library.aliases.len() != before
+    }
+
+    /// Returns the aliases with the names they stand for.
+    ///
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.library.aliases
+    }
+
+    /// Returns the canonical name of an author, following
+    /// the aliases.
+    ///
+    pub fn author(&self, name: &str) -> AuthorName {
+        let author = AuthorName::parse(name);
+        let key = author.key();
+        self.library
+            .aliases
+            .iter()
+            .find(|(alias, _)| AuthorName::parse(alias).key() == key)
+            .map(|(_, name)| AuthorName::parse(name))
+            .unwrap_or(author)
+    }
+
+    /// Returns every author with the books written,
+    /// sorted by surname.
+    ///
+    pub fn author_index(&self) -> Vec<AuthorEntry> {
+        let mut index: BTreeMap<String, AuthorEntry> = BTreeMap::new();
+        for volume in self.library.volumes.iter() {
+            let volume = self.effective(volume);
+            for name in volume.authors.iter() {
+                let author = self.author(name);
+                let key = author.key();
+                let entry = index.entry(key).or_insert_with(|| AuthorEntry {
+                    name: author.sort_name(),
+                    volumes: vec![],
+                });
+                if !entry.volumes.iter().any(|v| v.isbn == volume.isbn) {
+                    entry.volumes.push(volume.clone());
+                }
+            }
+        }
+        index.into_values().collect()
+    }
+}
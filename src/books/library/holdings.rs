@@ -0,0 +1,152 @@
+use super::LibraryService;
+use crate::books::Isbn;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A physical copy of a book
+///
+/// - id: barcode or accession number, unique in the library
+/// - location: where the copy is kept, e.g. a floor or a room
+/// - condition: free text, e.g. new, worn, damaged
+/// - acquired: acquisition date as YYYY-MM-DD
+/// - price: what the copy cost, e.g. 24.90 EUR
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquired: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum HoldingError {
+    #[error("{0} is not in the library")]
+    MissingBook(Isbn),
+    #[error("Copy {0} already exists")]
+    DuplicateCopy(String),
+    #[error("Copy {0} not found")]
+    UnknownCopy(String),
+    #[error("Copy {0} is lent to {1}")]
+    Lent(String, String),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Holding {
+    /// Replace the details set in the other holding,
+    /// keeping the id and the details left unset.
+    ///
+    pub fn update(&mut self, other: &Holding) {
+        let set = |field: &mut Option<String>, value: &Option<String>| {
+            if value.is_some() {
+                *field = value.clone();
+            }
+        };
+
+        set(&mut self.location, &other.location);
+        set(&mut self.condition, &other.condition);
+        set(&mut self.acquired, &other.acquired);
+        set(&mut self.price, &other.price);
+    }
+}
+
+impl LibraryService {
+    /// Add a physical copy of a library book.
+    ///
+    /// The copy gets an id made of the ISBN and a counter
+    /// when it has none.
+    ///
+    pub fn add_holding(
+        &mut self,
+        isbn: &Isbn,
+        mut holding: Holding,
+    ) -> Result<&Holding, HoldingError> {
+        if self.find(isbn).is_none() {
+            return Err(HoldingError::MissingBook(isbn.clone()));
+        }
+
+        holding.id = holding.id.trim().to_string();
+        if holding.id.is_empty() {
+            holding.id = (1..)
+                .map(|n| format!("{}-{}", isbn, n))
+                .find(|id| self.find_holding(id).is_none())
+                .unwrap_or_default();
+        } else if self.find_holding(&holding.id).is_some() {
+            return Err(HoldingError::DuplicateCopy(holding.id));
+        }
+
+        let copies = self.library.holdings.entry(isbn.to_string()).or_default();
+        copies.push(holding);
+        Ok(&copies[copies.len() - 1])
+    }
+
+    /// Update the details of a copy.
+    ///
+    pub fn update_holding(
+        &mut self,
+        changes: &Holding,
+    ) -> Result<&Holding, HoldingError> {
+        self.library
+            .holdings
+            .values_mut()
+            .flat_map(|copies| copies.iter_mut())
+            .find(|holding| holding.id == changes.id)
+            .map(|holding| {
+                holding.update(changes);
+                &*holding
+            })
+            .ok_or_else(|| HoldingError::UnknownCopy(changes.id.clone()))
+    }
+
+    /// Remove a copy, unless it is out on loan. The book stays
+    /// in the library even when its last copy is removed.
+    ///
+    /// Returns the ISBN of the copy removed.
+    ///
+    pub fn remove_holding(&mut self, id: &str) -> Result<Isbn, HoldingError> {
+        let (isbn, _) = self
+            .find_holding(id)
+            .ok_or_else(|| HoldingError::UnknownCopy(id.to_string()))?;
+        if let Some(loan) = self.open_loan(&isbn, Some(id)) {
+            return Err(HoldingError::Lent(id.to_string(), loan.borrower.clone()));
+        }
+        let key = isbn.to_string();
+
+        if let Some(copies) = self.library.holdings.get_mut(&key) {
+            copies.retain(|holding| holding.id != id);
+            if copies.is_empty() {
+                self.library.holdings.remove(&key);
+            }
+        }
+        Ok(isbn)
+    }
+
+    /// Returns the copy with the given id and the ISBN of its book.
+    ///
+    pub fn find_holding(&self, id: &str) -> Option<(Isbn, &Holding)> {
+        self.library.holdings.iter().find_map(|(isbn, copies)| {
+            copies
+                .iter()
+                .find(|holding| holding.id == id)
+                .map(|holding| (Isbn::new(isbn), holding))
+        })
+    }
+
+    /// Returns the copies of a book.
+    ///
+    pub fn holdings(&self, isbn: &Isbn) -> &[Holding] {
+        self.library
+            .holdings
+            .get(&isbn.to_string())
+            .map(|copies| copies.as_slice())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,206 @@
+use super::{LibraryService, ReadingStatus};
+use crate::books::{Isbn, Volume};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Tags and shelves to put on a library entry
+///
+/// Tags are case-insensitive and stored in lowercase.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Labels {
+    pub tags: BTreeSet<String>,
+    pub shelves: BTreeSet<String>,
+}
+
+/// Criteria selecting library entries, all of them must match
+///
+/// - tags: entries having all the tags
+/// - shelf: entries on the shelf
+/// - text: entries with the text in the title or in the authors
+/// - status: entries with the reading status
+///
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub tags: Vec<String>,
+    pub shelf: Option<String>,
+    pub text: Option<String>,
+    pub status: Option<ReadingStatus>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Labels {
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = normalize_tag(tag);
+        if !tag.is_empty() {
+            self.tags.insert(tag);
+        }
+    }
+
+    pub fn add_shelf(&mut self, shelf: &str) {
+        let shelf = shelf.trim();
+        if !shelf.is_empty() {
+            self.shelves.insert(shelf.to_string());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.shelves.is_empty()
+    }
+
+    /// Merge other labels into these ones
+    ///
+    pub fn extend(&mut self, other: &Labels) {
+        self.tags.extend(other.tags.iter().cloned());
+        self.shelves.extend(other.shelves.iter().cloned());
+    }
+}
+
+impl LibraryService {
+    /// Put the labels on a library entry.
+    ///
+    /// Returns None if the ISBN is not in the library, otherwise
+    /// whether the entry got a label it did not have.
+    ///
+    pub fn label(&mut self, isbn: &Isbn, labels: &Labels) -> Option<bool> {
+        self.find(isbn)?;
+
+        let key = isbn.to_string();
+        let mut changed = false;
+        for tag in labels.tags.iter() {
+            changed |= self
+                .library
+                .tags
+                .entry(key.clone())
+                .or_default()
+                .insert(tag.clone());
+        }
+        for shelf in labels.shelves.iter() {
+            changed |= self
+                .library
+                .shelves
+                .entry(shelf.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        Some(changed)
+    }
+
+    /// Take the labels off a library entry, forgetting the
+    /// tags and shelves left without books.
+    ///
+    /// Returns None if the ISBN is not in the library, otherwise
+    /// whether the entry had any of the labels.
+    ///
+    pub fn unlabel(&mut self, isbn: &Isbn, labels: &Labels) -> Option<bool> {
+        self.find(isbn)?;
+
+        let key = isbn.to_string();
+        let mut changed = false;
+        if let Some(tags) = self.library.tags.get_mut(&key) {
+            let count = tags.len();
+            tags.retain(|tag| !labels.tags.contains(tag));
+            changed |= tags.len() < count;
+            if tags.is_empty() {
+                self.library.tags.remove(&key);
+            }
+        }
+        for shelf in labels.shelves.iter() {
+            if let Some(isbns) = self.library.shelves.get_mut(shelf) {
+                changed |= isbns.remove(&key);
+                if isbns.is_empty() {
+                    self.library.shelves.remove(shelf);
+                }
+            }
+        }
+        Some(changed)
+    }
+
+    /// Returns the tags and shelves of a library entry.
+    ///
+    pub fn labels(&self, isbn: &Isbn) -> Labels {
+        let key = isbn.to_string();
+        Labels {
+            tags: self.library.tags.get(&key).cloned().unwrap_or_default(),
+            shelves: self
+                .library
+                .shelves
+                .iter()
+                .filter(|(_, isbns)| isbns.contains(&key))
+                .map(|(shelf, _)| shelf.clone())
+                .collect(),
+        }
+    }
+
+    /// Returns every tag with the count of its books.
+    ///
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts = BTreeMap::new();
+        for tags in self.library.tags.values() {
+            for tag in tags.iter() {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Returns every shelf with the count of its books.
+    ///
+    pub fn shelf_counts(&self) -> Vec<(String, usize)> {
+        self.library
+            .shelves
+            .iter()
+            .map(|(shelf, isbns)| (shelf.clone(), isbns.len()))
+            .collect()
+    }
+
+    /// Returns the library volumes matching the filter,
+    /// in library order, with the fields set by hand.
+    ///
+    pub fn select(&self, filter: &Filter) -> Vec<Volume> {
+        let tags: Vec<String> = filter.tags.iter().map(|t| normalize_tag(t)).collect();
+        let text = filter.text.as_ref().map(|text| text.to_lowercase());
+
+        self.library
+            .volumes
+            .iter()
+            .map(|volume| self.effective(volume))
+            .filter(|volume| {
+                let key = volume.isbn.to_string();
+                let volume_tags = self.library.tags.get(&key);
+                tags.iter()
+                    .all(|tag| volume_tags.is_some_and(|t| t.contains(tag)))
+            })
+            .filter(|volume| match &filter.shelf {
+                Some(shelf) => self
+                    .library
+                    .shelves
+                    .get(shelf)
+                    .is_some_and(|isbns| isbns.contains(&volume.isbn.to_string())),
+                None => true,
+            })
+            .filter(|volume| match &text {
+                Some(text) => {
+                    volume.title.to_lowercase().contains(text)
+                        || volume
+                            .authors
+                            .iter()
+                            .any(|author| author.to_lowercase().contains(text))
+                }
+                None => true,
+            })
+            .filter(|volume| match filter.status {
+                Some(status) => self
+                    .reading(&volume.isbn)
+                    .is_some_and(|reading| reading.status == Some(status)),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
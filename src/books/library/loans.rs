@@ -0,0 +1,194 @@
+use super::LibraryService;
+use crate::books::date::today;
+use crate::books::{Isbn, Volume};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A book lent to someone
+///
+/// - borrower: who took the book
+/// - lent: when the book was lent, as YYYY-MM-DD
+/// - due: when the book should be back, if agreed
+/// - returned: when the book came back, None while still out
+/// - copy: id of the copy lent, when the book has many
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loan {
+    pub borrower: String,
+    pub lent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub returned: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum LoanError {
+    #[error("{0} is not in the library")]
+    NotInLibrary(Isbn),
+    #[error("{0} is not a copy of {1}")]
+    UnknownCopy(String, Isbn),
+    #[error("{0} is already lent to {1}")]
+    AlreadyLent(String, String),
+    #[error("{0} is not lent")]
+    NotLent(String),
+    #[error("Several copies of {0} are lent, pick one with --copy")]
+    CopyRequired(Isbn),
+    #[error("Due on {0}, before the loan on {1}")]
+    DueBeforeLent(String, String),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Loan {
+    pub fn is_open(&self) -> bool {
+        self.returned.is_none()
+    }
+
+    /// True if the book is still out after the due date
+    ///
+    pub fn is_overdue(&self, today: &str) -> bool {
+        self.is_open() && self.due.as_deref().is_some_and(|due| due < today)
+    }
+}
+
+impl LibraryService {
+    /// Lend a library book, or one of its copies,
+    /// starting a new loan today.
+    ///
+    /// Without a copy, the first copy not out is lent when the
+    /// book has copies, otherwise the book as a whole.
+    ///
+    pub fn lend(
+        &mut self,
+        isbn: &Isbn,
+        borrower: &str,
+        due: Option<&str>,
+        copy: Option<&str>,
+    ) -> Result<&Loan, LoanError> {
+        self.check_copy(isbn, copy)?;
+        let lent = today();
+        if let Some(due) = due.filter(|due| *due < lent.as_str()) {
+            return Err(LoanError::DueBeforeLent(due.to_string(), lent));
+        }
+        let copy = copy.map(str::to_string).or_else(|| self.free_copy(isbn));
+        let copy = copy.as_deref();
+        if let Some(loan) = self.open_loan(isbn, copy) {
+            return Err(LoanError::AlreadyLent(
+                copy.map_or(isbn.to_string(), str::to_string),
+                loan.borrower.clone(),
+            ));
+        }
+
+        let history = self.library.loans.entry(isbn.to_string()).or_default();
+        history.push(Loan {
+            borrower: borrower.trim().to_string(),
+            lent,
+            due: due.map(|due| due.to_string()),
+            returned: None,
+            copy: copy.map(|copy| copy.to_string()),
+        });
+        Ok(&history[history.len() - 1])
+    }
+
+    /// Close the open loan of a book, or of one of its copies,
+    /// returned today.
+    ///
+    /// Without a copy, the only open loan of the book is closed;
+    /// when more copies are out the copy must be given.
+    ///
+    pub fn give_back(
+        &mut self,
+        isbn: &Isbn,
+        copy: Option<&str>,
+    ) -> Result<&Loan, LoanError> {
+        self.check_copy(isbn, copy)?;
+        let open = self
+            .loans(isbn)
+            .iter()
+            .filter(|loan| loan.is_open())
+            .count();
+        if copy.is_none() && open > 1 {
+            return Err(LoanError::CopyRequired(isbn.clone()));
+        }
+
+        self.library
+            .loans
+            .get_mut(&isbn.to_string())
+            .and_then(|history| {
+                history.iter_mut().find(|loan| {
+                    loan.is_open() && (copy.is_none() || loan.copy.as_deref() == copy)
+                })
+            })
+            .map(|loan| {
+                loan.returned = Some(today());
+                &*loan
+            })
+            .ok_or_else(|| {
+                LoanError::NotLent(copy.map_or(isbn.to_string(), str::to_string))
+            })
+    }
+
+    /// Returns the loan keeping a book, or one of its copies,
+    /// out, if any. Any open loan keeps out the book as a whole,
+    /// and a loan made without copy keeps out every copy.
+    ///
+    pub fn open_loan(&self, isbn: &Isbn, copy: Option<&str>) -> Option<&Loan> {
+        self.loans(isbn).iter().find(|loan| {
+            loan.is_open()
+                && (copy.is_none() || loan.copy.is_none() || loan.copy.as_deref() == copy)
+        })
+    }
+
+    /// Returns the loan history of a book, oldest first.
+    ///
+    pub fn loans(&self, isbn: &Isbn) -> &[Loan] {
+        self.library
+            .loans
+            .get(&isbn.to_string())
+            .map(|history| history.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the books out on loan with their loan,
+    /// in library order.
+    ///
+    pub fn lent_volumes(&self) -> Vec<(Volume, &Loan)> {
+        self.library
+            .volumes
+            .iter()
+            .flat_map(|volume| {
+                self.loans(&volume.isbn)
+                    .iter()
+                    .filter(|loan| loan.is_open())
+                    .map(move |loan| (self.effective(volume), loan))
+            })
+            .collect()
+    }
+
+    // The first copy of a book not out on loan, None when
+    // the book has no copies or all of them are lent
+    //
+    fn free_copy(&self, isbn: &Isbn) -> Option<String> {
+        self.holdings(isbn)
+            .iter()
+            .find(|holding| self.open_loan(isbn, Some(&holding.id)).is_none())
+            .map(|holding| holding.id.clone())
+    }
+
+    fn check_copy(&self, isbn: &Isbn, copy: Option<&str>) -> Result<(), LoanError> {
+        if self.find(isbn).is_none() {
+            return Err(LoanError::NotInLibrary(isbn.clone()));
+        }
+        match copy {
+            Some(id) if !self.holdings(isbn).iter().any(|holding| holding.id == id) => {
+                Err(LoanError::UnknownCopy(id.to_string(), isbn.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,431 @@
+mod authors;
+mod holdings;
+mod labels;
+mod loans;
+mod overrides;
+mod reading;
+mod series;
+mod stats;
+mod wishlist;
+mod works;
+
+use super::date::today;
+use super::google::{Candidate, Client, FetchError};
+use super::{FetchEvent, FetchObserver, Isbn, Storage, StorageError, Volume};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+use std::time::Duration;
+
+pub use authors::AuthorEntry;
+pub use holdings::{Holding, HoldingError};
+pub use labels::{Filter, Labels};
+pub use loans::{Loan, LoanError};
+pub use overrides::{Override, OverrideError};
+pub use reading::{Reading, ReadingError, ReadingStatus, ReadingUpdate};
+pub use series::SeriesEntry;
+pub use stats::{LibraryStats, Tally};
+pub use wishlist::{Wish, WishError};
+pub use works::{Duplicate, Work, WorkError};
+
+/// Storage for the downloaded Volumes list
+///
+/// - volumes: the books, as described by the provider
+/// - tags: the user tags of every book, by ISBN
+/// - shelves: the ISBN of the books on every named shelf
+/// - reading: the reading log of every book, by ISBN
+/// - loans: the loan history of every book, by ISBN
+/// - holdings: the physical copies of every book, by ISBN
+/// - overrides: the fields set by hand on every book, by ISBN
+/// - aliases: author spellings with the names they stand for
+/// - works: the ISBN of the editions merged in every work
+/// - wishlist: the books wanted, kept out of the owned volumes
+/// - added: the date every book entered the library, by ISBN;
+///   books added before the date was recorded have none
+///
+/// A volume is the bibliographic record of an ISBN, whatever
+/// the count of its copies.
+///
+/// The user data is kept apart from the volumes, so refreshing
+/// the provider metadata never overwrites it.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Library {
+    pub volumes: Vec<Volume>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub shelves: BTreeMap<String, BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reading: BTreeMap<String, Reading>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub loans: BTreeMap<String, Vec<Loan>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub holdings: BTreeMap<String, Vec<Holding>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub works: BTreeMap<String, BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wishlist: Vec<Wish>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub added: BTreeMap<String, String>,
+}
+
+/// High level service to manage the library structure.
+///
+/// It implements service api and load save the library from the disk
+/// abstracting the client to search book and the storage engine.
+///
+/// Once created call immediately the storage loading the library.
+///
+pub struct LibraryService {
+    client: Rc<Client>,
+    storage: Rc<Storage>,
+    library: Library,
+    observer: Option<Rc<dyn FetchObserver>>,
+}
+
+/// Summary of an append run
+///
+/// - input: the ISBN list provided
+/// - new_volumes: count of the volumes appended
+/// - low_confidence: appended volumes taken from a search result
+///   not matching the ISBN
+/// - outcomes: what happened to every input ISBN
+/// - library_size: count of the volumes in the library after the run
+/// - requests: count of the HTTP requests sent
+/// - requests_saved: volumes built from the search result alone,
+///   each saving a request to the volume endpoint
+///
+pub struct AppendStats {
+    pub input: Vec<Isbn>,
+    pub new_volumes: usize,
+    pub low_confidence: Vec<Isbn>,
+    pub outcomes: Vec<Outcome>,
+    pub library_size: usize,
+    pub requests: usize,
+    pub requests_saved: usize,
+}
+
+/// What happened to a single input ISBN
+///
+pub struct Outcome {
+    pub isbn: Isbn,
+    pub status: OutcomeStatus,
+    pub elapsed: Duration,
+}
+
+pub enum OutcomeStatus {
+    Skipped,
+    Fetched,
+    Failed(FetchError),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LibraryService {
+    /// Instantiate a new service. Loads the library immediately.
+    ///
+    pub fn new(client: Rc<Client>, storage: Rc<Storage>) -> LibraryService {
+        let library = storage.load();
+        LibraryService {
+            library,
+            client,
+            storage,
+            observer: None,
+        }
+    }
+
+    /// Set an observer notified while volumes are fetched.
+    ///
+    pub fn set_observer(&mut self, observer: Rc<dyn FetchObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Search new volumes and append them to the library.
+    ///
+    /// Uses the client to search new books, but before search
+    /// based on the ISBN filter the input list skipping ISBNs
+    /// already imported in the library.
+    ///
+    /// Once found, mutate the library and append the new books.
+    ///
+    pub async fn append_volumes(&mut self, list: &[Isbn]) -> AppendStats {
+        let (filtered, skipped) = self.identify_new_isbns(list);
+        let observer = self.observer.as_deref();
+
+        if let Some(observer) = observer {
+            observer.notify(&FetchEvent::Started {
+                total: filtered.len(),
+            });
+        }
+
+        let lookups = self.client.search_books(filtered, observer).await;
+
+        if let Some(observer) = observer {
+            observer.notify(&FetchEvent::Finished);
+        }
+
+        let mut outcomes: Vec<Outcome> = skipped
+            .into_iter()
+            .map(|isbn| Outcome {
+                isbn,
+                status: OutcomeStatus::Skipped,
+                elapsed: Duration::default(),
+            })
+            .collect();
+        let mut new_volumes = 0;
+        let mut low_confidence = vec![];
+        let mut requests = 0;
+        let mut requests_saved = 0;
+
+        for lookup in lookups.into_iter() {
+            requests += lookup.requests;
+            let status = match lookup.result {
+                Ok(volume) => {
+                    let flagged = volume.low_confidence;
+                    if lookup.from_search {
+                        requests_saved += 1;
+                    }
+                    match self.push_volume(volume) {
+                        true => {
+                            if flagged {
+                                low_confidence.push(lookup.isbn.clone());
+                            }
+                            new_volumes += 1;
+                            OutcomeStatus::Fetched
+                        }
+                        false => OutcomeStatus::Skipped,
+                    }
+                }
+                Err(e) => OutcomeStatus::Failed(e),
+            };
+            outcomes.push(Outcome {
+                isbn: lookup.isbn,
+                status,
+                elapsed: lookup.elapsed,
+            });
+        }
+
+        AppendStats {
+            input: list.to_vec(),
+            new_volumes,
+            low_confidence,
+            outcomes,
+            library_size: self.library.volumes.len(),
+            requests,
+            requests_saved,
+        }
+    }
+
+    /// Search a single volume and append it to the library,
+    /// unless its ISBN is already there.
+    ///
+    pub async fn append_volume(&mut self, isbn: &Isbn) -> Outcome {
+        if self.contains(isbn) {
+            return Outcome {
+                isbn: isbn.clone(),
+                status: OutcomeStatus::Skipped,
+                elapsed: Duration::default(),
+            };
+        }
+
+        let lookup = self.client.search_book(isbn).await;
+        let status = match lookup.result {
+            Ok(volume) => match self.push_volume(volume) {
+                true => OutcomeStatus::Fetched,
+                false => OutcomeStatus::Skipped,
+            },
+            Err(e) => OutcomeStatus::Failed(e),
+        };
+
+        Outcome {
+            isbn: lookup.isbn,
+            status,
+            elapsed: lookup.elapsed,
+        }
+    }
+
+    /// Fetch again the provider metadata of library volumes,
+    /// replacing the stored descriptions.
+    ///
+    /// The local file and the user data of the books are kept.
+    /// ISBN not in the library and books without a real ISBN
+    /// are skipped. A result not matching the ISBN fails as a
+    /// mismatch, keeping the stored volume.
+    ///
+    pub async fn refresh_volumes(&mut self, list: &[Isbn]) -> Vec<Outcome> {
+        let (known, skipped): (Vec<Isbn>, Vec<Isbn>) = list
+            .iter()
+            .cloned()
+            .partition(|isbn| !isbn.is_synthetic() && self.find(isbn).is_some());
+        let observer = self.observer.as_deref();
+
+        if let Some(observer) = observer {
+            observer.notify(&FetchEvent::Started { total: known.len() });
+        }
+
+        let lookups = self.client.search_books(known, observer).await;
+
+        if let Some(observer) = observer {
+            observer.notify(&FetchEvent::Finished);
+        }
+
+        let mut outcomes: Vec<Outcome> = skipped
+            .into_iter()
+            .map(|isbn| Outcome {
+                isbn,
+                status: OutcomeStatus::Skipped,
+                elapsed: Duration::default(),
+            })
+            .collect();
+
+        for lookup in lookups.into_iter() {
+            let isbn = lookup.isbn;
+            let status = match lookup.result {
+                Ok(volume) if volume.low_confidence => {
+                    OutcomeStatus::Failed(FetchError::Mismatch)
+                }
+                Ok(mut volume) => {
+                    if let Some(current) = self
+                        .library
+                        .volumes
+                        .iter_mut()
+                        .find(|current| current.isbn == isbn)
+                    {
+                        volume.isbn = current.isbn.clone();
+                        volume.file = current.file.take();
+                        *current = volume;
+                    }
+                    OutcomeStatus::Fetched
+                }
+                Err(e) => OutcomeStatus::Failed(e),
+            };
+            outcomes.push(Outcome {
+                isbn,
+                status,
+                elapsed: lookup.elapsed,
+            });
+        }
+
+        outcomes
+    }
+
+    /// Returns the ISBN of all the library volumes.
+    ///
+    pub fn isbns(&self) -> Vec<Isbn> {
+        self.library
+            .volumes
+            .iter()
+            .map(|volume| volume.isbn.clone())
+            .collect()
+    }
+
+    /// Append a volume built elsewhere, e.g. from a local file,
+    /// unless its ISBN is already in the library.
+    ///
+    /// Returns true if the volume was appended.
+    ///
+    pub fn add_volume(&mut self, volume: Volume) -> bool {
+        self.push_volume(volume)
+    }
+
+    /// Search a volume online without appending it.
+    ///
+    pub async fn search(&self, isbn: &Isbn) -> Result<Volume, FetchError> {
+        self.client.search_book(isbn).await.result
+    }
+
+    /// Search online the volumes matching a title and author,
+    /// ranked best first.
+    ///
+    pub async fn search_candidates(
+        &self,
+        title: &str,
+        author: Option<&str>,
+    ) -> Result<Vec<Candidate>, FetchError> {
+        self.client.search_candidates(title, author).await
+    }
+
+    /// Returns the library volume pointing to the given local file.
+    ///
+    pub fn find_file(&self, file: &str) -> Option<&Volume> {
+        self.library
+            .volumes
+            .iter()
+            .find(|volume| volume.file.as_deref() == Some(file))
+    }
+
+    /// Returns the library volume with the given ISBN.
+    ///
+    pub fn find(&self, isbn: &Isbn) -> Option<&Volume> {
+        self.library
+            .volumes
+            .iter()
+            .find(|volume| &volume.isbn == isbn)
+    }
+
+    /// True if a library volume has the ISBN, in its
+    /// ISBN-10 or ISBN-13 form.
+    ///
+    pub fn contains(&self, isbn: &Isbn) -> bool {
+        self.library
+            .volumes
+            .iter()
+            .any(|volume| &volume.isbn == isbn || volume.isbn.matches(isbn))
+    }
+
+    /// Save the library to the disk.
+    ///
+    pub fn save(&self) -> Result<(), StorageError> {
+        self.storage.save(&self.library).map_err(|e| {
+            StorageError::WriteError(self.storage.path().to_owned(), e.to_string())
+        })
+    }
+
+    /// Append a volume, added today, unless its ISBN is already
+    /// in the library. A wished book is owned from now on,
+    /// so it leaves the wishlist.
+    ///
+    /// Returns true if the volume was appended.
+    ///
+    fn push_volume(&mut self, volume: Volume) -> bool {
+        if self.contains(&volume.isbn) {
+            return false;
+        }
+        self.library
+            .added
+            .entry(volume.isbn.to_string())
+            .or_insert_with(today);
+        self.library
+            .wishlist
+            .retain(|wish| wish.volume.isbn != volume.isbn);
+        self.library.volumes.push(volume);
+        true
+    }
+
+    /// Filters ISBNs comparing every input ISBN with all the
+    /// ISBNs already loaded in the library and with the previous
+    /// input ones, so a book given twice, even once as ISBN-10
+    /// and once as ISBN-13, is searched once.
+    ///
+    /// Returns the new ISBNs and the ones skipped.
+    ///
+    fn identify_new_isbns(&self, isbns: &[Isbn]) -> (Vec<Isbn>, Vec<Isbn>) {
+        let mut list: Vec<Isbn> = vec![];
+        let mut skipped = vec![];
+        for isbn in isbns.iter() {
+            let repeated = list.iter().any(|x| x == isbn || x.matches(isbn));
+            match repeated || self.contains(isbn) {
+                true => skipped.push(isbn.clone()),
+                false => list.push(isbn.clone()),
+            }
+        }
+        (list, skipped)
+    }
+}
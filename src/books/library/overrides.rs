@@ -0,0 +1,143 @@
+use super::LibraryService;
+use crate::books::{Isbn, Volume};
+use thiserror::Error;
+
+/// A field set by hand on a library book
+///
+/// - provider: the value given by the provider
+/// - value: the value in effect
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Override {
+    pub isbn: Isbn,
+    pub field: String,
+    pub provider: String,
+    pub value: String,
+}
+
+#[derive(Error, Debug)]
+pub enum OverrideError {
+    #[error("{0} is not in the library")]
+    BookMissing(Isbn),
+    #[error("Unknown field \"{0}\", expected one of: {1}")]
+    UnknownField(String, String),
+    #[error("Invalid value \"{1}\" for {0}")]
+    InvalidValue(String, String),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LibraryService {
+    /// Set a field of a library book by hand.
+    ///
+    /// The value is kept apart from the provider data,
+    /// so it survives a refresh of the book.
+    ///
+    pub fn set_override(
+        &mut self,
+        isbn: &Isbn,
+        field: &str,
+        value: &str,
+    ) -> Result<(), OverrideError> {
+        let mut volume = self
+            .find(isbn)
+            .cloned()
+            .ok_or_else(|| OverrideError::BookMissing(isbn.clone()))?;
+        check_field(field)?;
+        if !volume.set_field(field, value) {
+            return Err(OverrideError::InvalidValue(
+                field.to_string(),
+                value.to_string(),
+            ));
+        }
+
+        self.library
+            .overrides
+            .entry(isbn.to_string())
+            .or_default()
+            .insert(field.to_string(), value.trim().to_string());
+        Ok(())
+    }
+
+    /// Drop a field set by hand, back to the provider value.
+    ///
+    /// Returns false if the field was not overridden.
+    ///
+    pub fn remove_override(
+        &mut self,
+        isbn: &Isbn,
+        field: &str,
+    ) -> Result<bool, OverrideError> {
+        if self.find(isbn).is_none() {
+            return Err(OverrideError::BookMissing(isbn.clone()));
+        }
+        check_field(field)?;
+
+        let key = isbn.to_string();
+        let removed = match self.library.overrides.get_mut(&key) {
+            Some(fields) => {
+                let removed = fields.remove(field).is_some();
+                if fields.is_empty() {
+                    self.library.overrides.remove(&key);
+                }
+                removed
+            }
+            None => false,
+        };
+        Ok(removed)
+    }
+
+    /// Returns the fields set by hand, by book in library order.
+    ///
+    pub fn overrides(&self) -> Vec<Override> {
+        self.library
+            .volumes
+            .iter()
+            .flat_map(|volume| {
+                self.library
+                    .overrides
+                    .get(&volume.isbn.to_string())
+                    .into_iter()
+                    .flatten()
+                    .map(move |(field, value)| Override {
+                        isbn: volume.isbn.clone(),
+                        field: field.clone(),
+                        provider: volume.field(field).unwrap_or_default(),
+                        value: value.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the library book with the given ISBN as in effect,
+    /// the provider data with the fields set by hand.
+    ///
+    pub fn volume(&self, isbn: &Isbn) -> Option<Volume> {
+        self.find(isbn).map(|volume| self.effective(volume))
+    }
+
+    /// Returns the provider data of a book with
+    /// the fields set by hand.
+    ///
+    pub fn effective(&self, volume: &Volume) -> Volume {
+        let mut volume = volume.clone();
+        if let Some(fields) = self.library.overrides.get(&volume.isbn.to_string()) {
+            for (field, value) in fields.iter() {
+                volume.set_field(field, value);
+            }
+        }
+        volume
+    }
+}
+
+fn check_field(field: &str) -> Result<(), OverrideError> {
+    match Volume::FIELDS.contains(&field) {
+        true => Ok(()),
+        false => Err(OverrideError::UnknownField(
+            field.to_string(),
+            Volume::FIELDS.join(", "),
+        )),
+    }
+}
@@ -0,0 +1,207 @@
+use super::LibraryService;
+use crate::books::date::today;
+use crate::books::Isbn;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Reading log of a library entry, owned by the user
+///
+/// - status: where the reading is at
+/// - rating: from 1 to 5
+/// - started, finished: reading dates as YYYY-MM-DD
+/// - notes: free-form personal notes
+/// - quotes: passages worth keeping
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reading {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ReadingStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quotes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReadingStatus {
+    ToRead,
+    Reading,
+    Read,
+    Abandoned,
+}
+
+/// Changes to apply to a reading log, unset fields are kept
+/// and the fields named in `clear` are emptied first
+///
+#[derive(Debug, Clone, Default)]
+pub struct ReadingUpdate {
+    pub status: Option<ReadingStatus>,
+    pub rating: Option<u8>,
+    pub started: Option<String>,
+    pub finished: Option<String>,
+    pub notes: Option<String>,
+    pub quotes: Vec<String>,
+    pub clear: Vec<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ReadingError {
+    #[error("{0} is not in the library")]
+    NotInLibrary(Isbn),
+    #[error("Finished on {finished}, before the start on {started}")]
+    FinishedBeforeStarted { started: String, finished: String },
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Reading {
+    /// Names of the fields which can be cleared
+    ///
+    pub const FIELDS: [&'static str; 6] =
+        ["status", "rating", "started", "finished", "notes", "quotes"];
+
+    /// Empty a field, returns false when the field is unknown
+    ///
+    pub fn clear(&mut self, name: &str) -> bool {
+        match name {
+            "status" => self.status = None,
+            "rating" => self.rating = None,
+            "started" => self.started = None,
+            "finished" => self.finished = None,
+            "notes" => self.notes = None,
+            "quotes" => self.quotes.clear(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Apply the changes. Starting a book sets the start date
+    /// and finishing it sets the finish date, when not given.
+    ///
+    /// The log is left unchanged when the result would be
+    /// finished before it was started.
+    ///
+    pub fn apply(&mut self, update: &ReadingUpdate) -> Result<(), ReadingError> {
+        let mut reading = self.clone();
+        for name in update.clear.iter() {
+            reading.clear(name);
+        }
+        if let Some(status) = update.status {
+            reading.status = Some(status);
+            match status {
+                ReadingStatus::Reading if reading.started.is_none() => {
+                    reading.started = Some(today())
+                }
+                ReadingStatus::Read if reading.finished.is_none() => {
+                    reading.finished = Some(today())
+                }
+                _ => (),
+            }
+        }
+        if update.rating.is_some() {
+            reading.rating = update.rating;
+        }
+        if update.started.is_some() {
+            reading.started = update.started.clone();
+        }
+        if update.finished.is_some() {
+            reading.finished = update.finished.clone();
+        }
+        if update.notes.is_some() {
+            reading.notes = update.notes.clone();
+        }
+        reading.quotes.extend(update.quotes.iter().cloned());
+
+        if let (Some(started), Some(finished)) = (&reading.started, &reading.finished) {
+            if finished < started {
+                return Err(ReadingError::FinishedBeforeStarted {
+                    started: started.clone(),
+                    finished: finished.clone(),
+                });
+            }
+        }
+        *self = reading;
+        Ok(())
+    }
+}
+
+impl ReadingUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.rating.is_none()
+            && self.started.is_none()
+            && self.finished.is_none()
+            && self.notes.is_none()
+            && self.quotes.is_empty()
+            && self.clear.is_empty()
+    }
+}
+
+impl ReadingStatus {
+    pub const NAMES: [&'static str; 4] = ["to-read", "reading", "read", "abandoned"];
+}
+
+impl FromStr for ReadingStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "to-read" => Ok(ReadingStatus::ToRead),
+            "reading" => Ok(ReadingStatus::Reading),
+            "read" => Ok(ReadingStatus::Read),
+            "abandoned" => Ok(ReadingStatus::Abandoned),
+            _ => Err(format!("unknown reading status \"{}\"", value)),
+        }
+    }
+}
+
+impl fmt::Display for ReadingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReadingStatus::ToRead => "to-read",
+            ReadingStatus::Reading => "reading",
+            ReadingStatus::Read => "read",
+            ReadingStatus::Abandoned => "abandoned",
+        };
+        f.write_str(name)
+    }
+}
+
+impl LibraryService {
+    /// Returns the reading log of a library entry.
+    ///
+    pub fn reading(&self, isbn: &Isbn) -> Option<&Reading> {
+        self.library.reading.get(&isbn.to_string())
+    }
+
+    /// Update the reading log of a library entry.
+    ///
+    /// Returns the updated log.
+    ///
+    pub fn update_reading(
+        &mut self,
+        isbn: &Isbn,
+        update: &ReadingUpdate,
+    ) -> Result<&Reading, ReadingError> {
+        if self.find(isbn).is_none() {
+            return Err(ReadingError::NotInLibrary(isbn.clone()));
+        }
+
+        let mut reading = self.reading(isbn).cloned().unwrap_or_default();
+        reading.apply(update)?;
+        let entry = self.library.reading.entry(isbn.to_string()).or_default();
+        *entry = reading;
+        Ok(entry)
+    }
+}
@@ -0,0 +1,65 @@
+use super::LibraryService;
+use crate::books::Volume;
+use std::collections::BTreeMap;
+
+/// A series with the library books belonging to it
+///
+/// - name: the name as first found in the library
+/// - volumes: the books in reading order, those without
+///   a position last
+/// - missing: the positions before the last owned one
+///   with no book in the library
+///
+#[derive(Debug, Clone)]
+pub struct SeriesEntry {
+    pub name: String,
+    pub volumes: Vec<Volume>,
+    pub missing: Vec<u32>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LibraryService {
+    /// Returns the series of the library books sorted by name.
+    /// Names differing only in case are the same series.
+    ///
+    pub fn series(&self) -> Vec<SeriesEntry> {
+        let mut series: BTreeMap<String, SeriesEntry> = BTreeMap::new();
+
+        for volume in self.library.volumes.iter() {
+            let volume = self.effective(volume);
+            let name = match &volume.series {
+                Some(name) => name.clone(),
+                None => continue,
+            };
+            series
+                .entry(name.to_lowercase())
+                .or_insert_with(|| SeriesEntry {
+                    name,
+                    volumes: vec![],
+                    missing: vec![],
+                })
+                .volumes
+                .push(volume);
+        }
+
+        series
+            .into_values()
+            .map(|mut entry| {
+                entry
+                    .volumes
+                    .sort_by_key(|volume| volume.series_index.unwrap_or(u32::MAX));
+                let owned: Vec<u32> = entry
+                    .volumes
+                    .iter()
+                    .filter_map(|v| v.series_index)
+                    .collect();
+                let last = owned.iter().max().copied().unwrap_or(0);
+                entry.missing = (1..last).filter(|n| !owned.contains(n)).collect();
+                entry
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,167 @@
+use super::LibraryService;
+use crate::books::Volume;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Fields checked for the metadata completeness, as
+/// reported missing by the volume
+///
+const COMPLETENESS_FIELDS: [&str; 8] = [
+    "title",
+    "authors",
+    "publisher",
+    "published_date",
+    "language",
+    "pages",
+    "description",
+    "image",
+];
+
+/// Label of the books without a value
+///
+const UNKNOWN: &str = "unknown";
+
+/// Label of the books added before the date was recorded
+///
+const NOT_RECORDED: &str = "not recorded";
+
+/// Aggregates over the library books
+///
+/// - volumes: count of the books
+/// - pages: pages of all the books, unknown page counts aside
+/// - authors: count of the authors, aliases followed
+/// - languages, publishers, by_author: books per value,
+///   most frequent first
+/// - decades: books per decade of publication, oldest first
+/// - added: books added per month as YYYY-MM, oldest first, the
+///   books added before the date was recorded last
+/// - completeness: books with a value for every descriptive field
+///
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LibraryStats {
+    pub volumes: usize,
+    pub pages: i64,
+    pub authors: usize,
+    pub languages: Vec<Tally>,
+    pub publishers: Vec<Tally>,
+    pub decades: Vec<Tally>,
+    pub by_author: Vec<Tally>,
+    pub added: Vec<Tally>,
+    pub completeness: Vec<Tally>,
+}
+
+/// Count of the books sharing a value
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Tally {
+    pub label: String,
+    pub count: usize,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LibraryService {
+    /// Compute the statistics of the library books, with
+    /// the fields set by hand.
+    ///
+    pub fn stats(&self) -> LibraryStats {
+        let volumes: Vec<Volume> = self
+            .library
+            .volumes
+            .iter()
+            .map(|volume| self.effective(volume))
+            .collect();
+        let by_author: Vec<Tally> = self
+            .author_index()
+            .into_iter()
+            .map(|entry| Tally {
+                label: entry.name,
+                count: entry.volumes.len(),
+            })
+            .collect();
+
+        let missing: Vec<Vec<&str>> = volumes
+            .iter()
+            .map(|volume| volume.missing_fields())
+            .collect();
+
+        LibraryStats {
+            volumes: volumes.len(),
+            pages: volumes.iter().map(|volume| volume.pages.max(0)).sum(),
+            authors: by_author.len(),
+            languages: most_frequent(tally(&volumes, UNKNOWN, |v| {
+                Some(v.language.clone())
+            })),
+            publishers: most_frequent(tally(&volumes, UNKNOWN, |v| {
+                Some(v.publisher.clone())
+            })),
+            decades: tally(&volumes, UNKNOWN, decade),
+            by_author: most_frequent(by_author),
+            added: tally(&volumes, NOT_RECORDED, |v| {
+                self.library
+                    .added
+                    .get(&v.isbn.to_string())
+                    .and_then(|date| date.get(..7))
+                    .map(String::from)
+            }),
+            completeness: COMPLETENESS_FIELDS
+                .iter()
+                .map(|field| Tally {
+                    label: field.to_string(),
+                    count: missing
+                        .iter()
+                        .filter(|fields| !fields.contains(field))
+                        .count(),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Counts the books per value sorted by value, the books
+// without a value last under the given label
+//
+fn tally<F>(volumes: &[Volume], missing: &str, value: F) -> Vec<Tally>
+where
+    F: Fn(&Volume) -> Option<String>,
+{
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut unknown = 0;
+    for volume in volumes.iter() {
+        match value(volume).filter(|value| !value.trim().is_empty()) {
+            Some(value) => *counts.entry(value.trim().to_string()).or_default() += 1,
+            None => unknown += 1,
+        }
+    }
+
+    let mut tallies: Vec<Tally> = counts
+        .into_iter()
+        .map(|(label, count)| Tally { label, count })
+        .collect();
+    if unknown > 0 {
+        tallies.push(Tally {
+            label: missing.to_string(),
+            count: unknown,
+        });
+    }
+    tallies
+}
+
+// Sorts the tallies most frequent first, keeping
+// the order of the values on a tie
+//
+fn most_frequent(mut tallies: Vec<Tally>) -> Vec<Tally> {
+    tallies.sort_by_key(|tally| Reverse(tally.count));
+    tallies
+}
+
+// Decade of publication from a date starting with the year,
+// e.g. "1980s" for "1983-05"
+//
+fn decade(volume: &Volume) -> Option<String> {
+    let year: i32 = volume.published_date.get(..4)?.parse().ok()?;
+    Some(format!("{}s", year - year.rem_euclid(10)))
+}
@@ -0,0 +1,129 @@
+use super::{Holding, HoldingError, LibraryService, Outcome, OutcomeStatus};
+use crate::books::date::today;
+use crate::books::{Isbn, Volume};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// A book wanted but not owned yet
+///
+/// - volume: the book, as described by the provider
+/// - added: date the book was wished as YYYY-MM-DD
+/// - note: free text, e.g. where to buy it or a gift idea
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wish {
+    pub volume: Volume,
+    pub added: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum WishError {
+    #[error("{0} is already in the library")]
+    AlreadyOwned(Isbn),
+    #[error("{0} is not in the wishlist")]
+    NotWished(Isbn),
+    #[error(transparent)]
+    Copy(#[from] HoldingError),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LibraryService {
+    /// Search a volume and add it to the wishlist, unless
+    /// it is already there. A note replaces the previous one.
+    ///
+    /// Fails if the book is already owned.
+    ///
+    pub async fn add_wish(
+        &mut self,
+        isbn: &Isbn,
+        note: Option<String>,
+    ) -> Result<Outcome, WishError> {
+        if self.contains(isbn) {
+            return Err(WishError::AlreadyOwned(isbn.clone()));
+        }
+        if let Some(wish) = self.find_wish_mut(isbn) {
+            if note.is_some() {
+                wish.note = note;
+            }
+            return Ok(Outcome {
+                isbn: isbn.clone(),
+                status: OutcomeStatus::Skipped,
+                elapsed: Duration::default(),
+            });
+        }
+
+        let lookup = self.client.search_book(isbn).await;
+        let status = match lookup.result {
+            Ok(volume) => {
+                self.library.wishlist.push(Wish {
+                    volume,
+                    added: today(),
+                    note,
+                });
+                OutcomeStatus::Fetched
+            }
+            Err(e) => OutcomeStatus::Failed(e),
+        };
+
+        Ok(Outcome {
+            isbn: lookup.isbn,
+            status,
+            elapsed: lookup.elapsed,
+        })
+    }
+
+    /// Take a book out of the wishlist.
+    ///
+    pub fn remove_wish(&mut self, isbn: &Isbn) -> Result<Wish, WishError> {
+        let position = self
+            .library
+            .wishlist
+            .iter()
+            .position(|wish| &wish.volume.isbn == isbn)
+            .ok_or_else(|| WishError::NotWished(isbn.clone()))?;
+        Ok(self.library.wishlist.remove(position))
+    }
+
+    /// Move a wished book to the library with a new copy,
+    /// acquired today unless the copy tells otherwise.
+    ///
+    /// A book already owned keeps its record and gets
+    /// the copy only.
+    ///
+    pub fn acquire(
+        &mut self,
+        isbn: &Isbn,
+        mut holding: Holding,
+    ) -> Result<&Holding, WishError> {
+        let id = holding.id.trim();
+        if !id.is_empty() && self.find_holding(id).is_some() {
+            return Err(HoldingError::DuplicateCopy(id.to_string()).into());
+        }
+
+        let wish = self.remove_wish(isbn)?;
+        self.add_volume(wish.volume);
+        if holding.acquired.is_none() {
+            holding.acquired = Some(today());
+        }
+        Ok(self.add_holding(isbn, holding)?)
+    }
+
+    /// Returns the wished books, oldest first.
+    ///
+    pub fn wishes(&self) -> &[Wish] {
+        &self.library.wishlist
+    }
+
+    fn find_wish_mut(&mut self, isbn: &Isbn) -> Option<&mut Wish> {
+        self.library
+            .wishlist
+            .iter_mut()
+            .find(|wish| &wish.volume.isbn == isbn)
+    }
+}
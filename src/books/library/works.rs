@@ -0,0 +1,297 @@
+use super::LibraryService;
+use crate::books::{Isbn, Volume};
+use std::collections::{BTreeSet, HashMap};
+use strsim::jaro_winkler;
+use thiserror::Error;
+
+/// Editions of the same work: hardcovers, paperbacks, translations
+///
+/// - title: the title of the first edition in the library
+/// - editions: the volumes, in library order
+///
+#[derive(Debug, Clone)]
+pub struct Work {
+    pub title: String,
+    pub editions: Vec<Volume>,
+}
+
+/// Two volumes likely to be the same book or the same work
+///
+/// - score: similarity of titles and authors, from 0 to 1
+///
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    pub first: Volume,
+    pub second: Volume,
+    pub score: f64,
+}
+
+#[derive(Error, Debug)]
+pub enum WorkError {
+    #[error("{0} is not in the library")]
+    EditionMissing(Isbn),
+    #[error("{0} was not merged in a work")]
+    NotMerged(Isbn),
+    #[error("A work needs at least two distinct editions")]
+    SingleEdition,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl LibraryService {
+    /// Fold library books into a single work with many editions.
+    /// Works the books already belong to are folded too.
+    ///
+    /// Repeated ISBNs count once, and at least two distinct
+    /// editions are needed.
+    ///
+    /// Returns the count of the editions of the work.
+    ///
+    pub fn merge_works(&mut self, isbns: &[Isbn]) -> Result<usize, WorkError> {
+        if let Some(isbn) = isbns.iter().find(|isbn| self.find(isbn).is_none()) {
+            return Err(WorkError::EditionMissing(isbn.clone()));
+        }
+
+        let mut editions: BTreeSet<String> = isbns.iter().map(Isbn::to_string).collect();
+        if editions.len() < 2 {
+            return Err(WorkError::SingleEdition);
+        }
+        let mut key = isbns.first().map(Isbn::to_string).unwrap_or_default();
+        let merged: Vec<String> = self
+            .library
+            .works
+            .iter()
+            .filter(|(_, members)| !members.is_disjoint(&editions))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if let Some(first) = merged.first() {
+            key = first.clone();
+        }
+        for name in merged.iter() {
+            if let Some(members) = self.library.works.remove(name) {
+                editions.extend(members);
+            }
+        }
+
+        let count = editions.len();
+        self.library.works.insert(key, editions);
+        Ok(count)
+    }
+
+    /// Take a book out of the work it was merged in,
+    /// forgetting the works left with a single edition.
+    ///
+    pub fn unmerge_work(&mut self, isbn: &Isbn) -> Result<(), WorkError> {
+        if self.find(isbn).is_none() {
+            return Err(WorkError::EditionMissing(isbn.clone()));
+        }
+
+        let key = isbn.to_string();
+        let name = self
+            .library
+            .works
+            .iter()
+            .find(|(_, members)| members.contains(&key))
+            .map(|(name, _)| name.clone())
+            .ok_or_else(|| WorkError::NotMerged(isbn.clone()))?;
+
+        if let Some(mut members) = self.library.works.remove(&name) {
+            members.remove(&key);
+            if members.len() > 1 {
+                let first = members.iter().next().cloned().unwrap_or_default();
+                let name = if name == key { first } else { name };
+                self.library.works.insert(name, members);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the works with more than one edition in the library.
+    ///
+    /// Editions are grouped by the work id given by the provider,
+    /// and by the works merged by hand. An edition linked to no
+    /// other joins the work of the most similar book, by title
+    /// and authors, when the score reaches `min_score`. Works
+    /// linked apart are never joined on similarity alone.
+    ///
+    pub fn works(&self, min_score: f64) -> Vec<Work> {
+        let mut groups = self.work_groups();
+        let volumes = self.effective_volumes();
+        let authors = self.author_keys(&volumes);
+        let linked = |groups: &[usize], index: usize| {
+            groups
+                .iter()
+                .enumerate()
+                .any(|(other, group)| other != index && *group == groups[index])
+        };
+
+        for i in 0..volumes.len() {
+            if linked(&groups, i) {
+                continue;
+            }
+            let best = (0..volumes.len())
+                .filter(|j| *j != i)
+                .map(|j| {
+                    let score =
+                        similarity(&volumes[i], &volumes[j], &authors[i], &authors[j]);
+                    (j, score)
+                })
+                .filter(|(_, score)| *score >= min_score)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((j, _)) = best {
+                join(&mut groups, i, j);
+            }
+        }
+
+        let mut works: Vec<Work> = vec![];
+        let mut positions: HashMap<usize, usize> = HashMap::new();
+
+        for (index, volume) in volumes.into_iter().enumerate() {
+            match positions.get(&groups[index]) {
+                Some(position) => works[*position].editions.push(volume),
+                None => {
+                    positions.insert(groups[index], works.len());
+                    works.push(Work {
+                        title: volume.title.clone(),
+                        editions: vec![volume],
+                    });
+                }
+            }
+        }
+
+        works.retain(|work| work.editions.len() > 1);
+        works
+    }
+
+    /// Returns the pairs of books with similar titles and authors
+    /// not in the same work yet, most similar first.
+    ///
+    pub fn duplicates(&self, min_score: f64) -> Vec<Duplicate> {
+        let groups = self.work_groups();
+        let volumes = self.effective_volumes();
+        let authors = self.author_keys(&volumes);
+
+        let mut duplicates = vec![];
+        for i in 0..volumes.len() {
+            for j in i + 1..volumes.len() {
+                if groups[i] == groups[j] {
+                    continue;
+                }
+                let score =
+                    similarity(&volumes[i], &volumes[j], &authors[i], &authors[j]);
+                if score >= min_score {
+                    duplicates.push(Duplicate {
+                        first: volumes[i].clone(),
+                        second: volumes[j].clone(),
+                        score,
+                    });
+                }
+            }
+        }
+
+        duplicates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        duplicates
+    }
+
+    // Returns the group of every library volume, by position:
+    // volumes of the same work share the same group
+    //
+    fn work_groups(&self) -> Vec<usize> {
+        let volumes = &self.library.volumes;
+        let mut groups: Vec<usize> = (0..volumes.len()).collect();
+        let mut first_of: HashMap<String, usize> = HashMap::new();
+
+        for (index, volume) in volumes.iter().enumerate() {
+            let isbn = volume.isbn.to_string();
+            let mut keys: Vec<String> = vec![];
+            if let Some(id) = &volume.work_id {
+                keys.push(format!("id:{}", id));
+            }
+            for (name, members) in self.library.works.iter() {
+                if members.contains(&isbn) {
+                    keys.push(format!("merged:{}", name));
+                }
+            }
+
+            for key in keys {
+                match first_of.get(&key) {
+                    Some(first) => join(&mut groups, index, *first),
+                    None => {
+                        first_of.insert(key, index);
+                    }
+                }
+            }
+        }
+        groups
+    }
+
+    // The library volumes with their overrides applied
+    //
+    fn effective_volumes(&self) -> Vec<Volume> {
+        self.library
+            .volumes
+            .iter()
+            .map(|volume| self.effective(volume))
+            .collect()
+    }
+
+    // The author keys of every volume, matched as in the author index
+    //
+    fn author_keys(&self, volumes: &[Volume]) -> Vec<BTreeSet<String>> {
+        volumes
+            .iter()
+            .map(|volume| {
+                volume
+                    .authors
+                    .iter()
+                    .map(|name| self.author(name).key())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Move the volume at `index`, with its whole group,
+/// in the group of the volume at `other`
+///
+fn join(groups: &mut [usize], index: usize, other: usize) {
+    let (from, to) = (groups[index], groups[other]);
+    for group in groups.iter_mut() {
+        if *group == from {
+            *group = to;
+        }
+    }
+}
+
+/// Title similarity, averaged with the share of common authors.
+/// Titles weigh more than authors, and the subtitles are ignored.
+///
+fn similarity(
+    a: &Volume,
+    b: &Volume,
+    a_authors: &BTreeSet<String>,
+    b_authors: &BTreeSet<String>,
+) -> f64 {
+    let title_score = jaro_winkler(&normalize(&a.title), &normalize(&b.title));
+    let author_score = match a_authors.len().max(b_authors.len()) {
+        0 => 0.0,
+        total => a_authors.intersection(b_authors).count() as f64 / total as f64,
+    };
+    title_score * 0.7 + author_score * 0.3
+}
+
+/// Lowercase words of the main title, without punctuation
+///
+fn normalize(title: &str) -> String {
+    title
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
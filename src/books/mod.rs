@@ -1,10 +1,23 @@
+mod author;
+mod digest;
+mod events;
 mod isbn;
 mod library;
+mod series;
 mod storage;
 mod volume;
 
+pub mod date;
+pub mod epub;
 pub mod google;
+pub use author::AuthorName;
+pub use events::{FetchEvent, FetchObserver};
 pub use isbn::Isbn;
-pub use library::{AppendStats, Library, LibraryService};
-pub use storage::Storage;
+pub use library::{
+    AppendStats, AuthorEntry, Duplicate, Filter, Holding, HoldingError, Labels, Library,
+    LibraryService, LibraryStats, Loan, LoanError, Outcome, OutcomeStatus, Override,
+    OverrideError, Reading, ReadingError, ReadingStatus, ReadingUpdate, SeriesEntry,
+    Tally, Wish, WishError, Work, WorkError,
+};
+pub use storage::{Storage, StorageError};
 pub use volume::Volume;
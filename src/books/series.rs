@@ -0,0 +1,105 @@
+//! Series membership read from titles and subtitles, e.g.
+//! "The Fellowship of the Ring (The Lord of the Rings, #1)"
+//! or a "Book 3 of the Discworld" subtitle.
+//!
+//! A leading "The" is dropped from the series name, whatever
+//! its case, so every pattern reads the same name.
+
+use regex::Regex;
+
+/// Title ending with the series in parentheses:
+/// "Title (Series, #3)", "Title (Series Book 3)"
+///
+const TITLE_PATTERN: &str = r"(?i)\(\s*(?:the\s+)?(?P<name>[^()#]+?),?\s*(?:#|book|vol\.?|volume|part)\s*(?P<n>\w+)\s*\)\s*$";
+
+/// Subtitle with the position first: "Book 3", "Volume 3 of Series"
+///
+const POSITION_PATTERN: &str = r"(?i)^(?:book|vol\.?|volume|part|#)\s*(?P<n>\w+)(?:\s+(?:of|in)\s+(?:the\s+)?(?P<name>.+))?$";
+
+/// Subtitle with the series first: "Series, Book 3", "Series #3"
+///
+const SERIES_PATTERN: &str =
+    r"(?i)^(?:the\s+)?(?P<name>.+?),?\s+(?:book|vol\.?|volume|part|#)\s*(?P<n>\w+)$";
+
+/// Position of a volume in a series, with the series name when known
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesInfo {
+    pub name: Option<String>,
+    pub index: u32,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Look for the series of a book in its title and subtitle
+///
+pub fn parse_series(title: &str, subtitle: &str) -> Option<SeriesInfo> {
+    let patterns = [
+        (TITLE_PATTERN, title),
+        (POSITION_PATTERN, subtitle.trim()),
+        (SERIES_PATTERN, subtitle.trim()),
+    ];
+
+    patterns.iter().find_map(|(pattern, text)| {
+        let pattern = Regex::new(pattern).expect("valid series pattern");
+        let captures = pattern.captures(text)?;
+        let index = number(&captures["n"])?;
+        let name = captures
+            .name("name")
+            .map(|name| name.as_str().trim().to_string())
+            .filter(|name| !name.is_empty());
+        Some(SeriesInfo { name, index })
+    })
+}
+
+/// Position written in digits or in words, from one to twelve
+///
+pub fn number(text: &str) -> Option<u32> {
+    const WORDS: [&str; 12] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve",
+    ];
+
+    text.parse::<u32>().ok().or_else(|| {
+        let text = text.to_lowercase();
+        WORDS
+            .iter()
+            .position(|word| *word == text)
+            .map(|position| position as u32 + 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(title: &str, subtitle: &str) -> Option<String> {
+        parse_series(title, subtitle).and_then(|info| info.name)
+    }
+
+    #[test]
+    fn drops_the_article_whatever_its_case() {
+        let expected = Some("Discworld".to_string());
+
+        assert_eq!(name("Mort", "Book 4 of the Discworld"), expected);
+        assert_eq!(name("Mort", "Book 4 of The Discworld"), expected);
+        assert_eq!(name("Mort", "Book 4 of THE Discworld"), expected);
+        assert_eq!(name("Mort (The Discworld, #4)", ""), expected);
+        assert_eq!(name("Mort (the Discworld, #4)", ""), expected);
+        assert_eq!(name("Mort", "The Discworld, Book 4"), expected);
+    }
+
+    #[test]
+    fn keeps_the_article_inside_the_name() {
+        assert_eq!(
+            name("The Two Towers (The Lord of the Rings, #2)", ""),
+            Some("Lord of the Rings".to_string())
+        );
+        assert_eq!(
+            name("Theft", "Book 2 of Thera Chronicles"),
+            Some("Thera Chronicles".to_string())
+        );
+    }
+}
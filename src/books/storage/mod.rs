@@ -1,6 +1,7 @@
 use super::Library;
-use std::fs::File;
+use std::fs::{rename, File};
 use std::io::{BufReader, Error};
+use thiserror::Error as ThisError;
 
 /// Storage service, load and save the library to disk.
 ///
@@ -10,6 +11,14 @@ pub struct Storage {
     path: String,
 }
 
+/// Storage error
+///
+#[derive(ThisError, Debug)]
+pub enum StorageError {
+    #[error("Impossible to write library \"{0}\": {1}")]
+    WriteError(String, String),
+}
+
 /////////////////////////////////////////////////////////////////////////////
 // Type implementation
 /////////////////////////////////////////////////////////////////////////////
@@ -21,6 +30,10 @@ impl Storage {
         }
     }
 
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
     pub fn load(&self) -> Library {
         File::open(&self.path).map_or(Library::default(), |file| {
             let reader = BufReader::new(file);
@@ -28,8 +41,15 @@ impl Storage {
         })
     }
 
+    /// Write the library to a temporary file, then move it in place
+    /// so an interrupted write never corrupts the library.
+    ///
     pub fn save(&self, library: &Library) -> Result<(), Error> {
-        File::create(&self.path)
-            .and_then(|file| serde_json::to_writer_pretty(file, library).map_err(Error::from))
+        let temp = format!("{}.tmp", self.path);
+        File::create(&temp)
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, library).map_err(Error::from)
+            })
+            .and_then(|_| rename(&temp, &self.path))
     }
 }
@@ -12,4 +12,154 @@ pub struct Volume {
     pub language: String,
     pub authors: Vec<String>,
     pub pages: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub low_confidence: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_index: Option<u32>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Volume {
+    /// Names of the descriptive fields which can be set by hand
+    ///
+    pub const FIELDS: [&'static str; 10] = [
+        "title",
+        "description",
+        "publisher",
+        "published_date",
+        "image",
+        "language",
+        "authors",
+        "pages",
+        "series",
+        "series_index",
+    ];
+
+    /// Value of a descriptive field as text, authors
+    /// separated by semicolons
+    ///
+    pub fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "title" => Some(self.title.clone()),
+            "description" => Some(self.description.clone()),
+            "publisher" => Some(self.publisher.clone()),
+            "published_date" => Some(self.published_date.clone()),
+            "image" => Some(self.image.clone()),
+            "language" => Some(self.language.clone()),
+            "authors" => Some(self.authors.join("; ")),
+            "pages" => Some(self.pages.to_string()),
+            "series" => Some(self.series.clone().unwrap_or_default()),
+            "series_index" => Some(
+                self.series_index
+                    .map(|index| index.to_string())
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Set a descriptive field from text, authors
+    /// separated by semicolons. An empty series or
+    /// series index clears it.
+    ///
+    /// Returns false when the field is unknown
+    /// or the value is not valid for it.
+    ///
+    pub fn set_field(&mut self, name: &str, value: &str) -> bool {
+        let value = value.trim().to_string();
+        match name {
+            "title" => self.title = value,
+            "description" => self.description = value,
+            "publisher" => self.publisher = value,
+            "published_date" => self.published_date = value,
+            "image" => self.image = value,
+            "language" => self.language = value,
+            "authors" => {
+                self.authors = value
+                    .split(';')
+                    .map(|author| author.trim().to_string())
+                    .filter(|author| !author.is_empty())
+                    .collect()
+            }
+            "pages" => match value.parse::<i64>() {
+                Ok(pages) if pages >= 0 => self.pages = pages,
+                _ => return false,
+            },
+            "series" => self.series = Some(value).filter(|name| !name.is_empty()),
+            "series_index" if value.is_empty() => self.series_index = None,
+            "series_index" => match value.parse::<u32>() {
+                Ok(index) => self.series_index = Some(index),
+                Err(_) => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// Names of the descriptive fields left empty
+    ///
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        let fields = [
+            ("title", self.title.is_empty()),
+            ("description", self.description.is_empty()),
+            ("publisher", self.publisher.is_empty()),
+            ("published_date", self.published_date.is_empty()),
+            ("image", self.image.is_empty()),
+            ("language", self.language.is_empty()),
+            ("authors", self.authors.is_empty()),
+            ("pages", self.pages == 0),
+        ];
+
+        fields
+            .iter()
+            .filter(|(_, missing)| *missing)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// Fill the empty fields with the values of another volume
+    /// describing the same book.
+    ///
+    pub fn fill_from(&mut self, other: &Volume) {
+        let fill = |field: &mut String, value: &String| {
+            if field.is_empty() {
+                *field = value.clone();
+            }
+        };
+
+        fill(&mut self.title, &other.title);
+        fill(&mut self.description, &other.description);
+        fill(&mut self.publisher, &other.publisher);
+        fill(&mut self.published_date, &other.published_date);
+        fill(&mut self.image, &other.image);
+        fill(&mut self.language, &other.language);
+        if self.authors.is_empty() {
+            self.authors = other.authors.clone();
+        }
+        if self.pages == 0 {
+            self.pages = other.pages;
+        }
+        if self.work_id.is_none() {
+            self.work_id = other.work_id.clone();
+        }
+        if self.series.is_none() {
+            self.series = other.series.clone();
+        }
+        if self.series_index.is_none() {
+            self.series_index = other.series_index;
+        }
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
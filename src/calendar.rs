@@ -0,0 +1,84 @@
+//! iCalendar (RFC 5545) export of all-day events, e.g. to
+//! subscribe a calendar application to the loan due dates.
+
+use crate::books::date::{next_day, timestamp};
+
+/// An all-day event
+///
+/// - uid: identifier stable across exports, so calendar
+///   applications update the event instead of duplicating it
+/// - date: day of the event as YYYY-MM-DD, ending the day after
+///
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub uid: String,
+    pub date: String,
+    pub summary: String,
+    pub description: String,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Returns the iCalendar document of the events
+///
+pub fn to_ics(events: &[Event]) -> String {
+    let stamp = timestamp();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//books//{}//EN", env!("CARGO_PKG_VERSION")),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for event in events.iter() {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!(
+            "DTSTART;VALUE=DATE:{}",
+            event.date.replace('-', "")
+        ));
+        if let Some(end) = next_day(&event.date) {
+            lines.push(format!("DTEND;VALUE=DATE:{}", end.replace('-', "")));
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+// Escapes the characters with a meaning in text values
+//
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Ends the line with CRLF, folding it every 75 octets
+// without splitting a character
+//
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
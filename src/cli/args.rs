@@ -1,16 +1,543 @@
-use clap::Parser;
+use crate::books::date::is_valid_date;
+use crate::books::{Reading, ReadingStatus};
+use clap::{ArgEnum, Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
     #[clap(short, long)]
     pub input_file: Option<String>,
 
-    #[clap(short, long, default_value_t = String::from("./library.json"), env="BOOK_OUTPUT")]
+    /// Extract ISBN from free text, HTML, PDF or EPUB input
+    /// instead of reading a plain list
+    #[clap(long)]
+    pub extract: bool,
+
+    /// Decode ISBN barcodes from JPEG/PNG images or folders of images
+    #[clap(
+        long,
+        multiple_values = true,
+        min_values = 1,
+        conflicts_with = "input-file"
+    )]
+    pub from_images: Vec<String>,
+
+    /// Tag every fetched book, can be repeated
+    #[clap(long = "tag", value_name = "TAG", multiple_occurrences = true)]
+    pub tags: Vec<String>,
+
+    /// Put every fetched book on a shelf
+    #[clap(long, value_name = "NAME")]
+    pub shelf: Option<String>,
+
+    #[clap(global = true, short, long, default_value_t = String::from("./library.json"), env="BOOK_OUTPUT")]
     pub output_file: String,
 
-    #[clap(short, long, default_value_t = String::from("~/.books/config.json"), env="BOOK_CONFIG")]
+    #[clap(global = true, short, long, default_value_t = String::from("~/.books/config.json"), env="BOOK_CONFIG")]
     pub config_file: String,
 
+    /// Country code (ISO 3166-1) sent to the API
+    #[clap(global = true, long)]
+    pub country: Option<String>,
+
+    /// Restrict search results to a language (ISO 639-1)
+    #[clap(global = true, long)]
+    pub lang_restrict: Option<String>,
+
+    /// Restrict search results to a print type
+    #[clap(global = true, long, possible_values = ["all", "books", "magazines"])]
+    pub print_type: Option<String>,
+
+    /// Serve the API responses from the cache only, without network
+    #[clap(global = true, long)]
+    pub offline: bool,
+
+    /// Save the API responses in a folder as test fixtures
+    #[clap(global = true, long, value_name = "DIR", conflicts_with = "offline")]
+    pub record: Option<String>,
+
+    /// Increase log verbosity (-v info, -vv debug, -vvv trace)
+    #[clap(global = true, short, long, parse(from_occurrences))]
+    pub verbose: u64,
+
+    /// Silence all log output
+    #[clap(global = true, short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Do not display the fetch progress
+    #[clap(long)]
+    pub no_progress: bool,
+
+    /// Write logs to a file instead of stderr
+    #[clap(global = true, long)]
+    pub log_file: Option<String>,
+
+    /// Write a machine readable report of the run
+    #[clap(long, arg_enum)]
+    pub report: Option<ReportFormat>,
+
+    /// Write the report to a file instead of stdout
+    #[clap(long, requires = "report")]
+    pub report_file: Option<String>,
+
     pub isbn_list: Vec<String>,
 }
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Read ISBN from a barcode scanner (or stdin) line by line,
+    /// fetching and saving every book right away
+    Scan,
+
+    /// Build library entries from a folder of EPUB files,
+    /// reading their metadata without network lookups
+    ScanEbooks {
+        /// Folder to scan, subfolders included
+        dir: String,
+
+        /// Complete the volumes having an ISBN with the data found online
+        #[clap(long)]
+        enrich: bool,
+    },
+
+    /// Search a book without ISBN by title and author,
+    /// choosing among the ranked candidates found
+    Lookup {
+        /// Title of the book
+        #[clap(long)]
+        title: String,
+
+        /// Author of the book
+        #[clap(long)]
+        author: Option<String>,
+
+        /// Take the best candidate when its score, from 0 to 1,
+        /// is at least this value
+        #[clap(long, value_name = "SCORE", validator = check_score)]
+        accept_above: Option<f64>,
+    },
+
+    /// Manage the API response cache
+    Cache {
+        #[clap(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Add or remove tags on library books
+    Tag {
+        #[clap(subcommand)]
+        action: LabelAction,
+    },
+
+    /// Put books on named shelves or collections, or take them off
+    Shelf {
+        #[clap(subcommand)]
+        action: LabelAction,
+    },
+
+    /// List the library books
+    List {
+        #[clap(flatten)]
+        filter: FilterArgs,
+    },
+
+    /// Search the library books by title or author
+    Search {
+        /// Text to find in titles and authors
+        text: String,
+
+        #[clap(flatten)]
+        filter: FilterArgs,
+    },
+
+    /// Show or update the reading log of a book: status,
+    /// rating, dates, notes and quotes
+    Reading {
+        /// ISBN of the book
+        isbn: String,
+
+        #[clap(flatten)]
+        update: ReadingArgs,
+    },
+
+    /// Lend a book to someone
+    Lend {
+        /// ISBN of the book
+        isbn: String,
+
+        /// Who borrows the book
+        #[clap(long, value_name = "NAME")]
+        to: String,
+
+        /// When the book should be back
+        #[clap(long, value_name = "YYYY-MM-DD", validator = check_date)]
+        due: Option<String>,
+
+        /// Id of the copy lent
+        #[clap(long, value_name = "ID")]
+        copy: Option<String>,
+    },
+
+    /// Mark a lent book as returned
+    Return {
+        /// ISBN of the book
+        isbn: String,
+
+        /// Id of the copy returned
+        #[clap(long, value_name = "ID")]
+        copy: Option<String>,
+    },
+
+    /// List every author with the books written, or manage
+    /// the author aliases
+    Authors {
+        #[clap(subcommand)]
+        action: Option<AuthorAction>,
+    },
+
+    /// Manage the physical copies of the books
+    Copy {
+        #[clap(subcommand)]
+        action: CopyAction,
+    },
+
+    /// Manage the books wanted, kept apart from the library
+    Wish {
+        #[clap(subcommand)]
+        action: WishAction,
+    },
+
+    /// Show the books out on loan, or the loan history of a book
+    Loans {
+        /// ISBN of the book
+        isbn: Option<String>,
+
+        /// Write the due dates to an iCalendar file
+        #[clap(long, value_name = "FILE", conflicts_with = "isbn")]
+        ics: Option<String>,
+    },
+
+    /// Show the books out on loan after their due date
+    Overdue,
+
+    /// Set fields of a book by hand, kept over the provider data
+    Edit {
+        /// ISBN of the book
+        isbn: String,
+
+        /// Set a field, can be repeated. Authors are separated by ';'
+        #[clap(
+            long = "set",
+            value_name = "FIELD=VALUE",
+            multiple_occurrences = true,
+            validator = check_assignment,
+            required_unless_present = "unset"
+        )]
+        set: Vec<String>,
+
+        /// Drop a field set by hand, back to the provider value
+        #[clap(long = "unset", value_name = "FIELD", multiple_occurrences = true)]
+        unset: Vec<String>,
+    },
+
+    /// Show the fields set by hand on the library books
+    Overrides,
+
+    /// Show the works with many editions in the library
+    Works {
+        /// Lowest similarity score grouping a book linked to no
+        /// other work, from 0 to 1
+        #[clap(
+            long,
+            value_name = "SCORE",
+            default_value_t = 0.85,
+            validator = check_score
+        )]
+        min_score: f64,
+    },
+
+    /// Show the series in reading order, with the volumes
+    /// missing from the library
+    Series,
+
+    /// Show the library statistics: totals, breakdowns and
+    /// how complete the metadata is
+    Stats {
+        /// Output format
+        #[clap(long, arg_enum, default_value = "table")]
+        format: StatsFormat,
+
+        /// Write an HTML report with charts instead
+        #[clap(long, value_name = "FILE")]
+        html: Option<String>,
+
+        /// Count of the publishers and authors shown in tables and charts
+        #[clap(long, value_name = "COUNT", default_value_t = 10)]
+        top: usize,
+    },
+
+    /// Show the books likely to be duplicates or editions
+    /// of the same work, with their similarity score
+    Duplicates {
+        /// Lowest similarity score shown, from 0 to 1
+        #[clap(
+            long,
+            value_name = "SCORE",
+            default_value_t = 0.85,
+            validator = check_score
+        )]
+        min_score: f64,
+    },
+
+    /// Fold books into one work with several editions
+    Merge {
+        /// ISBN of the editions
+        #[clap(required = true, min_values = 2)]
+        isbns: Vec<String>,
+    },
+
+    /// Take a book out of the work it was merged in
+    Unmerge {
+        /// ISBN of the edition
+        isbn: String,
+    },
+
+    /// Fetch again the metadata of the library books,
+    /// keeping tags, shelves and reading logs
+    Refresh {
+        /// ISBN of the books, all the library when omitted
+        isbns: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LabelAction {
+    /// Add the label to the books
+    Add {
+        /// Tag or shelf name
+        name: String,
+
+        /// ISBN of the books
+        #[clap(required = true)]
+        isbns: Vec<String>,
+    },
+    /// Remove the label from the books
+    Remove {
+        /// Tag or shelf name
+        name: String,
+
+        /// ISBN of the books
+        #[clap(required = true)]
+        isbns: Vec<String>,
+    },
+    /// Show the labels in use with their books count
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthorAction {
+    /// Make a spelling of an author stand for another name
+    Alias {
+        /// Spelling to replace, e.g. a pen name
+        alias: String,
+
+        /// Name to use instead
+        name: String,
+    },
+    /// Forget an alias
+    Unalias {
+        /// Spelling of the alias
+        alias: String,
+    },
+    /// Show the aliases with the names they stand for
+    Aliases,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CopyAction {
+    /// Add a copy of a book, fetching the book when not in the library
+    Add {
+        /// ISBN of the book
+        isbn: String,
+
+        /// Barcode or accession number, generated when omitted
+        #[clap(long)]
+        id: Option<String>,
+
+        #[clap(flatten)]
+        details: CopyArgs,
+    },
+    /// Change the details of a copy
+    Update {
+        /// Id of the copy
+        id: String,
+
+        #[clap(flatten)]
+        details: CopyArgs,
+    },
+    /// Remove a copy
+    Remove {
+        /// Id of the copy
+        id: String,
+    },
+    /// Show the copies of a book, or of the whole library
+    List {
+        /// ISBN of the book
+        isbn: Option<String>,
+    },
+}
+
+/// Details of a physical copy
+///
+#[derive(clap::Args, Debug)]
+pub struct CopyArgs {
+    /// Where the copy is kept
+    #[clap(long)]
+    pub location: Option<String>,
+
+    /// Condition of the copy, e.g. new, worn, damaged
+    #[clap(long)]
+    pub condition: Option<String>,
+
+    /// Acquisition date
+    #[clap(long, value_name = "YYYY-MM-DD", validator = check_date)]
+    pub acquired: Option<String>,
+
+    /// Price paid, e.g. "24.90 EUR"
+    #[clap(long)]
+    pub price: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WishAction {
+    /// Fetch a book and add it to the wishlist
+    Add {
+        /// ISBN of the book
+        isbn: String,
+
+        /// Free text, e.g. where to buy it
+        #[clap(long)]
+        note: Option<String>,
+    },
+    /// Move a wished book to the library, acquired today by default
+    Acquire {
+        /// ISBN of the book
+        isbn: String,
+
+        /// Barcode or accession number of the copy, generated when omitted
+        #[clap(long)]
+        id: Option<String>,
+
+        #[clap(flatten)]
+        details: CopyArgs,
+    },
+    /// Take a book out of the wishlist
+    Remove {
+        /// ISBN of the book
+        isbn: String,
+    },
+    /// Show the wished books
+    List,
+}
+
+/// Options selecting library books
+///
+#[derive(clap::Args, Debug)]
+pub struct FilterArgs {
+    /// Only the books with this tag, can be repeated
+    #[clap(long = "tag", value_name = "TAG", multiple_occurrences = true)]
+    pub tags: Vec<String>,
+
+    /// Only the books on this shelf
+    #[clap(long, value_name = "NAME")]
+    pub shelf: Option<String>,
+
+    /// Only the books with this reading status
+    #[clap(long, possible_values = ReadingStatus::NAMES)]
+    pub status: Option<ReadingStatus>,
+}
+
+/// Changes to the reading log of a book
+///
+#[derive(clap::Args, Debug)]
+pub struct ReadingArgs {
+    /// Reading status, starting or finishing a book
+    /// sets today as start or finish date
+    #[clap(long, possible_values = ReadingStatus::NAMES)]
+    pub status: Option<ReadingStatus>,
+
+    /// Rating from 1 to 5
+    #[clap(long, possible_values = ["1", "2", "3", "4", "5"])]
+    pub rating: Option<u8>,
+
+    /// Date the reading started
+    #[clap(long, value_name = "YYYY-MM-DD", validator = check_date)]
+    pub started: Option<String>,
+
+    /// Date the reading finished
+    #[clap(long, value_name = "YYYY-MM-DD", validator = check_date)]
+    pub finished: Option<String>,
+
+    /// Personal notes, replacing the previous ones
+    #[clap(long)]
+    pub notes: Option<String>,
+
+    /// Add a quote, can be repeated
+    #[clap(long = "quote", value_name = "TEXT", multiple_occurrences = true)]
+    pub quotes: Vec<String>,
+
+    /// Empty a field before the other changes, can be repeated
+    #[clap(
+        long,
+        value_name = "FIELD",
+        possible_values = Reading::FIELDS,
+        multiple_occurrences = true
+    )]
+    pub clear: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Show the cached responses count and size
+    Stats,
+    /// Remove all the cached responses
+    Clear,
+    /// Remove the expired cached responses
+    Prune,
+}
+
+#[derive(ArgEnum, Clone, Debug)]
+pub enum ReportFormat {
+    Json,
+    Jsonl,
+}
+
+#[derive(ArgEnum, Clone, Debug)]
+pub enum StatsFormat {
+    Table,
+    Json,
+}
+
+fn check_date(value: &str) -> Result<(), String> {
+    match is_valid_date(value) {
+        true => Ok(()),
+        false => Err(String::from("expected a date as YYYY-MM-DD")),
+    }
+}
+
+fn check_score(value: &str) -> Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(score) if (0.0..=1.0).contains(&score) => Ok(()),
+        _ => Err(String::from("expected a score from 0 to 1")),
+    }
+}
+
+fn check_assignment(value: &str) -> Result<(), String> {
+    match value.split_once('=') {
+        Some((field, _)) if !field.trim().is_empty() => Ok(()),
+        _ => Err(String::from("expected FIELD=VALUE")),
+    }
+}
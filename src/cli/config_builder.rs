@@ -37,7 +37,7 @@ impl ConfigBuilder {
         let config_file = ConfigBuilder::create(&cli.config_file, None)?;
 
         let mut config = ConfigBuilder::load(&config_file);
-        config.set_default(&cli);
+        config.set_default(cli);
 
         if !config.is_valid() {
             return Err(BuildError::InvalidConfig);
@@ -78,9 +78,7 @@ impl ConfigBuilder {
                 })
                 .unwrap_or_else(|| Err(BuildError::ConfigDirNotDefined));
 
-            if let Err(x) = result {
-                return Err(x);
-            };
+            result?;
         }
 
         let path = expanded.unwrap();
@@ -1,5 +1,8 @@
 mod args;
 mod config_builder;
 
-pub use args::Args;
+pub use args::{
+    Args, AuthorAction, CacheAction, Command, CopyAction, CopyArgs, FilterArgs,
+    LabelAction, ReadingArgs, ReportFormat, StatsFormat, WishAction,
+};
 pub use config_builder::{BuildError, ConfigBuilder};
@@ -1,17 +1,41 @@
 use super::Args;
+use crate::books::google::{CacheConfig, HttpConfig, MismatchPolicy};
 use serde::{Deserialize, Serialize};
 
 /// App config structure
 ///
 /// - base_url: Url for the book resolution service
-/// - api_key: API key for the book resolution service
+/// - api_key: API key for the book resolution service, empty for keyless access
 /// - output: Output path for the library store
+/// - country: Country code sent with every request
+/// - lang_restrict: Language code restricting the search results
+/// - print_type: Print type restricting the search results
+/// - on_isbn_mismatch: `flag` or `reject` the search results not listing the ISBN
+/// - http: timeouts, proxy, certificates and headers of the HTTP client
+/// - cache: folder, TTL and offline mode of the response cache
+/// - record: folder where the API responses are saved as fixtures,
+///   set from the command line only
 ///
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub base_url: String,
+    #[serde(default)]
     pub api_key: String,
     pub output: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang_restrict: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub print_type: Option<String>,
+    #[serde(default)]
+    pub on_isbn_mismatch: MismatchPolicy,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(skip)]
+    pub record: Option<String>,
 }
 
 /////////////////////////////////////////////////////////////////////////////
@@ -24,6 +48,13 @@ impl ::std::default::Default for Config {
             base_url: "".into(),
             api_key: "".into(),
             output: "library.json".into(),
+            country: None,
+            lang_restrict: None,
+            print_type: None,
+            on_isbn_mismatch: MismatchPolicy::default(),
+            http: HttpConfig::default(),
+            cache: CacheConfig::default(),
+            record: None,
         }
     }
 }
@@ -33,13 +64,32 @@ impl Config {
     ///
     pub fn set_default(&mut self, arguments: &Args) -> &Config {
         self.output = arguments.output_file.clone();
+
+        if arguments.country.is_some() {
+            self.country = arguments.country.clone();
+        }
+        if arguments.lang_restrict.is_some() {
+            self.lang_restrict = arguments.lang_restrict.clone();
+        }
+        if arguments.print_type.is_some() {
+            self.print_type = arguments.print_type.clone();
+        }
+        if arguments.offline {
+            self.cache.offline = true;
+        }
+        if arguments.record.is_some() {
+            self.record = arguments.record.clone();
+        }
         self
     }
 
-    /// Base url and api key are needed by the client
-    /// Not having them should flag the config as invalid
+    /// Base url is needed by the client, not having it
+    /// should flag the config as invalid.
+    ///
+    /// The api key is optional: without it the API is
+    /// queried anonymously with a lower quota.
     ///
     pub fn is_valid(&self) -> bool {
-        self.base_url != "" && self.api_key != ""
+        !self.base_url.is_empty()
     }
 }
@@ -0,0 +1,121 @@
+//! Self-contained HTML report of the library statistics, with
+//! the charts drawn as inline SVG so the page needs no network.
+
+use crate::books::{LibraryStats, Tally};
+
+const STYLE: &str =
+    "body{font-family:sans-serif;margin:2em auto;max-width:60em;color:#222}\
+h1{font-weight:normal}\
+.totals{display:flex;gap:2em}\
+.totals div{font-size:2em}\
+.totals span{display:block;font-size:.5em;color:#666}\
+svg text{font-size:12px;fill:#222}\
+rect{fill:#4a7ab5}";
+
+const ROW_HEIGHT: usize = 22;
+const LABEL_WIDTH: usize = 220;
+const BAR_WIDTH: usize = 400;
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Returns the HTML document of the statistics, showing
+/// the `top` most frequent publishers and authors.
+///
+pub fn to_html(stats: &LibraryStats, top: usize) -> String {
+    let mut html = vec![
+        "<!DOCTYPE html>".to_string(),
+        "<html lang=\"en\">".to_string(),
+        "<head>".to_string(),
+        "<meta charset=\"utf-8\">".to_string(),
+        "<title>Library statistics</title>".to_string(),
+        format!("<style>{}</style>", STYLE),
+        "</head>".to_string(),
+        "<body>".to_string(),
+        "<h1>Library statistics</h1>".to_string(),
+        "<section class=\"totals\">".to_string(),
+        total("volumes", stats.volumes as i64),
+        total("pages", stats.pages),
+        total("authors", stats.authors as i64),
+        "</section>".to_string(),
+    ];
+
+    let top_of =
+        |tallies: &[Tally]| tallies.iter().take(top).cloned().collect::<Vec<_>>();
+    html.push(chart("Languages", &stats.languages));
+    html.push(chart("Publishers", &top_of(&stats.publishers)));
+    html.push(chart("Decades of publication", &stats.decades));
+    html.push(chart("Authors", &top_of(&stats.by_author)));
+    html.push(chart("Books added per month", &stats.added));
+    html.push(chart("Books with the field set", &stats.completeness));
+
+    html.push("</body>".to_string());
+    html.push("</html>".to_string());
+    html.join("\n")
+}
+
+// A total with its caption
+//
+fn total(caption: &str, value: i64) -> String {
+    format!("<div>{}<span>{}</span></div>", value, caption)
+}
+
+// A section with a horizontal bar chart of the tallies
+//
+fn chart(title: &str, tallies: &[Tally]) -> String {
+    let max = tallies
+        .iter()
+        .map(|tally| tally.count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let height = tallies.len() * ROW_HEIGHT;
+    let width = LABEL_WIDTH + BAR_WIDTH + 60;
+
+    let mut svg = vec![format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\">",
+        width, height
+    )];
+    for (row, tally) in tallies.iter().enumerate() {
+        let y = row * ROW_HEIGHT;
+        let bar = (tally.count * BAR_WIDTH / max).max(1);
+        svg.push(format!(
+            "<text x=\"{0}\" y=\"{1}\" text-anchor=\"end\">{2}</text>",
+            LABEL_WIDTH - 8,
+            y + 15,
+            escape(&tally.label)
+        ));
+        svg.push(format!(
+            "<rect x=\"{0}\" y=\"{1}\" width=\"{2}\" height=\"{3}\"/>",
+            LABEL_WIDTH,
+            y + 3,
+            bar,
+            ROW_HEIGHT - 6
+        ));
+        svg.push(format!(
+            "<text x=\"{0}\" y=\"{1}\">{2}</text>",
+            LABEL_WIDTH + bar + 6,
+            y + 15,
+            tally.count
+        ));
+    }
+    svg.push("</svg>".to_string());
+
+    format!(
+        "<section>\n<h2>{}</h2>\n{}\n</section>",
+        escape(title),
+        svg.join("\n")
+    )
+}
+
+// Escapes the characters with a meaning in HTML
+//
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
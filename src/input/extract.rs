@@ -0,0 +1,149 @@
+use crate::books::epub::Package;
+use crate::books::Isbn;
+use regex::Regex;
+use std::fs::read_to_string;
+use std::path::Path;
+
+/// ISBN-10 or ISBN-13 candidates, optionally prefixed by an
+/// "ISBN", "ISBN-10:" or "ISBN-13:" label. Digit groups can be
+/// separated by hyphens or single spaces.
+///
+const ISBN_PATTERN: &str =
+    r"(?i)(?:\bISBN(?:-1[03])?:?\s*|\b)((?:97[89][- ]?)?(?:\d[- ]?){9}[\dX])\b";
+
+/// HTML tags and comments
+///
+const TAG_PATTERN: &str = r"(?s)<!--.*?-->|<[^>]*>";
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Scan a free text for ISBN, keeping only the candidates
+/// with a valid check digit. Duplicates are removed.
+///
+pub fn extract_isbns(text: &str) -> Vec<Isbn> {
+    let pattern = Regex::new(ISBN_PATTERN).expect("valid ISBN pattern");
+    let mut list: Vec<Isbn> = vec![];
+
+    for candidate in pattern.captures_iter(text).filter_map(|c| c.get(1)) {
+        let value: String = candidate
+            .as_str()
+            .chars()
+            .filter(|c| !matches!(c, '-' | ' '))
+            .collect();
+        let isbn = Isbn::new(&value.to_uppercase());
+
+        if isbn.is_valid() && !list.contains(&isbn) {
+            list.push(isbn);
+        }
+    }
+
+    list
+}
+
+/// Extract the ISBN from a document, reading it by file extension:
+/// text from PDF, identifiers from EPUB package metadata,
+/// visible text from HTML and everything else as plain text.
+///
+pub fn extract_from_file(path: &Path) -> Result<Vec<Isbn>, String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "pdf" => read_pdf(path).map(|text| extract_isbns(&text)),
+        "epub" => Package::open(path)
+            .map(|package| package.isbns())
+            .map_err(|e| e.to_string()),
+        "html" | "htm" | "xhtml" => read_to_string(path)
+            .map(|html| extract_isbns(&strip_html(&html)))
+            .map_err(|e| e.to_string()),
+        _ => read_to_string(path)
+            .map(|text| extract_isbns(&text))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// True for the documents which cannot be read as text
+/// and always need the extraction.
+///
+pub fn is_document(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_lowercase().as_str(), "pdf" | "epub"))
+        .unwrap_or(false)
+}
+
+/// Remove tags and comments from HTML, decoding the
+/// entities which could split an ISBN.
+///
+pub fn strip_html(html: &str) -> String {
+    let tags = Regex::new(TAG_PATTERN).expect("valid tag pattern");
+
+    tags.replace_all(html, " ")
+        .replace("&nbsp;", " ")
+        .replace("&#8209;", "-")
+        .replace("&ndash;", "-")
+        .replace("&#8211;", "-")
+        .replace("&amp;", "&")
+}
+
+/// Read the text content of a PDF file
+///
+fn read_pdf(path: &Path) -> Result<String, String> {
+    pdf_extract::extract_text(path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(isbns: Vec<Isbn>) -> Vec<String> {
+        isbns.into_iter().map(|isbn| isbn.value).collect()
+    }
+
+    #[test]
+    fn reads_hyphenated_and_spaced_isbns() {
+        let text = "ISBN-13: 978-0-262-03384-8, then 978 0 596 00712 6.";
+
+        assert_eq!(
+            values(extract_isbns(text)),
+            vec!["9780262033848", "9780596007126"]
+        );
+    }
+
+    #[test]
+    fn reads_isbn10_with_x_check_digit() {
+        assert_eq!(
+            values(extract_isbns("ISBN 0-8044-2957-X")),
+            vec!["080442957X"]
+        );
+        assert_eq!(values(extract_isbns("isbn 080442957x")), vec!["080442957X"]);
+    }
+
+    #[test]
+    fn rejects_a_bad_check_digit() {
+        assert!(extract_isbns("ISBN 978-0-262-03384-9 and 0-8044-2957-1").is_empty());
+    }
+
+    #[test]
+    fn reads_isbns_split_by_html_tags_and_entities() {
+        let html = "<p>ISBN:&nbsp;<b>978&#8209;0&#8209;262&#8209;03384&#8209;8</b></p>\
+                    <!-- 9780596007126 --><span>0&ndash;8044&ndash;2957&ndash;X</span>";
+
+        assert_eq!(
+            values(extract_isbns(&strip_html(html))),
+            vec!["9780262033848", "080442957X"]
+        );
+    }
+
+    #[test]
+    fn keeps_one_copy_of_a_repeated_isbn() {
+        let text = "9780262033848, again 978-0-262-03384-8";
+
+        assert_eq!(values(extract_isbns(text)), vec!["9780262033848"]);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::books::Isbn;
+use rxing::helpers::detect_multiple_in_luma_with_hints;
+use rxing::{BarcodeFormat, DecodeHintValue, DecodeHints, Exceptions};
+use std::collections::HashSet;
+use std::fs::read_dir;
+use std::io::Result as IoResult;
+use std::path::{Path, PathBuf};
+
+/// Image extensions scanned when a folder is provided
+///
+const EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+
+/// Barcodes decoded from a single image
+///
+/// - isbns: Bookland EAN-13 codes found
+/// - ignored: other EAN-13 codes found, e.g. price or product codes
+/// - error: the reason why the image could not be decoded
+///
+#[derive(Debug, Default)]
+pub struct ImageScan {
+    pub path: PathBuf,
+    pub isbns: Vec<Isbn>,
+    pub ignored: Vec<String>,
+    pub error: Option<String>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Decode the barcodes from a list of images or folders of images.
+///
+/// Folders are not scanned recursively, only the JPEG and PNG
+/// files they contain are decoded, sorted by name.
+///
+pub fn scan_images(paths: &[String]) -> IoResult<Vec<ImageScan>> {
+    let mut files = vec![];
+    for path in paths.iter().map(PathBuf::from) {
+        if path.is_dir() {
+            files.extend(list_images(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(files.into_iter().map(scan_image).collect())
+}
+
+/// Decode the barcodes from a single image file.
+///
+fn scan_image(path: PathBuf) -> ImageScan {
+    let mut scan = ImageScan {
+        path,
+        ..ImageScan::default()
+    };
+
+    match decode(&scan.path) {
+        Ok(codes) => {
+            for code in codes {
+                let isbn = Isbn::new(&code);
+                match isbn.is_bookland() {
+                    true if !scan.isbns.contains(&isbn) => scan.isbns.push(isbn),
+                    true => (),
+                    false => scan.ignored.push(code),
+                }
+            }
+        }
+        Err(e) => scan.error = Some(e),
+    };
+
+    scan
+}
+
+/// Load the image in grayscale and look for EAN-13 barcodes.
+///
+fn decode(path: &Path) -> Result<Vec<String>, String> {
+    let image = image::open(path).map_err(|e| e.to_string())?.to_luma8();
+    let (width, height) = image.dimensions();
+
+    let mut hints = DecodeHints::default()
+        .with(DecodeHintValue::PossibleFormats(HashSet::from([
+            BarcodeFormat::EAN_13,
+        ])))
+        .with(DecodeHintValue::TryHarder(true));
+
+    match detect_multiple_in_luma_with_hints(image.into_raw(), width, height, &mut hints)
+    {
+        Ok(results) => Ok(results
+            .iter()
+            .map(|result| result.getText().to_string())
+            .collect()),
+        Err(Exceptions::NotFoundException(_)) => Ok(vec![]),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// List the image files in a folder.
+///
+fn list_images(dir: &Path) -> IoResult<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && has_image_extension(path))
+        .collect();
+
+    files.sort();
+    Ok(files)
+}
+
+fn has_image_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
@@ -0,0 +1,260 @@
+extern crate atty;
+
+mod extract;
+mod images;
+
+use crate::books::{Isbn, Labels};
+use crate::cli::Args;
+use atty::{is, Stream};
+use extract::{extract_from_file, extract_isbns, is_document, strip_html};
+use images::{scan_images, ImageScan};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{stdin, Read, Result as IoResult};
+use std::path::Path;
+use thiserror::Error;
+
+/// Delegate to read input
+///
+pub struct InputReader;
+
+/// The ISBN to fetch with the labels to put on them
+///
+/// Labels come from the `--tag` and `--shelf` options, applied to
+/// every ISBN, and from the `#tag` and `@shelf` annotations of the
+/// input lines, applied to the ISBN of the same line.
+///
+#[derive(Debug, Default)]
+pub struct Input {
+    pub isbns: Vec<Isbn>,
+    pub labels: HashMap<String, Labels>,
+}
+
+/// Input error to wrap all errors
+///
+#[derive(Error, Debug)]
+pub enum InputError {
+    #[error("No input file provided.")]
+    NoFileProvided,
+    #[error("Cannot read from file \"{0}\": {1}")]
+    ReadFileError(String, String),
+    #[error("Impossible to read stdin: {0}")]
+    IoError(String),
+    #[error("Cannot read images: {0}")]
+    ImagesError(String),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl InputReader {
+    pub fn read(args: &Args) -> Result<Input, InputError> {
+        let mut input = read_input(args)?;
+
+        let mut common = Labels::default();
+        for tag in args.tags.iter() {
+            common.add_tag(tag);
+        }
+        if let Some(shelf) = &args.shelf {
+            common.add_shelf(shelf);
+        }
+        if !common.is_empty() {
+            for isbn in input.isbns.iter() {
+                input
+                    .labels
+                    .entry(isbn.to_string())
+                    .or_default()
+                    .extend(&common);
+            }
+        }
+
+        Ok(input)
+    }
+}
+
+impl From<Vec<Isbn>> for Input {
+    fn from(isbns: Vec<Isbn>) -> Input {
+        Input {
+            isbns,
+            labels: HashMap::new(),
+        }
+    }
+}
+
+/// Read the input data from the possible input sources
+///
+fn read_input(args: &Args) -> Result<Input, InputError> {
+    if !args.from_images.is_empty() {
+        return read_images(&args.from_images).map(Input::from);
+    }
+
+    let has_arguments = !args.isbn_list.is_empty();
+    let has_file_option = args.input_file.is_some();
+
+    if !has_arguments {
+        if has_file_option {
+            let argument = args.input_file.clone();
+            let file = argument.ok_or(InputError::NoFileProvided)?;
+            let path = Path::new(&file);
+            if args.extract || is_document(path) {
+                return extract_from_file(path)
+                    .map(Input::from)
+                    .map_err(|e| InputError::ReadFileError(file.clone(), e));
+            }
+            let string = read_file(&file)
+                .map_err(|e| InputError::ReadFileError(file, e.to_string()))?;
+            let input = parse_input_to_isbn(&string);
+            return Ok(input);
+        } else if has_stream_data() {
+            let string =
+                read_input_stream().map_err(|e| InputError::IoError(e.to_string()))?;
+            if args.extract {
+                return Ok(Input::from(extract_from_text(&string)));
+            }
+            let input = parse_input_to_isbn(&string);
+            return Ok(input);
+        }
+    }
+
+    if args.extract {
+        return Ok(Input::from(extract_from_text(&args.isbn_list.join(" "))));
+    }
+
+    Ok(Input::from(list_to_isbn(args)))
+}
+
+/// Extract the ISBN from a free text, removing the
+/// markup first if the text looks like HTML.
+///
+fn extract_from_text(text: &str) -> Vec<Isbn> {
+    match text.trim_start().starts_with('<') {
+        true => extract_isbns(&strip_html(text)),
+        false => extract_isbns(text),
+    }
+}
+
+/// Convert the arg input strings to a list of Isbn
+///
+fn list_to_isbn(args: &Args) -> Vec<Isbn> {
+    map_list_to_isbn(args.isbn_list.clone())
+}
+
+/// Map list of strings to a list of Isbn
+///
+fn map_list_to_isbn(list: Vec<String>) -> Vec<Isbn> {
+    list.iter().map(|s| Isbn::new(s)).collect()
+}
+
+/// Map an input string to a list of Isbn
+///
+/// Words starting with `#` tag and words starting with `@`
+/// shelve the ISBN of their line.
+///
+fn parse_input_to_isbn(data: &str) -> Input {
+    let mut input = Input::default();
+
+    for line in data.lines() {
+        let mut isbns = vec![];
+        let mut labels = Labels::default();
+        for word in line.split(' ') {
+            if let Some(tag) = word.strip_prefix('#') {
+                labels.add_tag(tag);
+            } else if let Some(shelf) = word.strip_prefix('@') {
+                labels.add_shelf(shelf);
+            } else {
+                isbns.push(Isbn::new(word));
+            }
+        }
+
+        if !labels.is_empty() {
+            for isbn in isbns.iter() {
+                input
+                    .labels
+                    .entry(isbn.to_string())
+                    .or_default()
+                    .extend(&labels);
+            }
+        }
+        input.isbns.extend(isbns);
+    }
+
+    input
+}
+
+/// Decode the ISBN barcodes from the images, printing
+/// on stderr what was found in every image.
+///
+fn read_images(paths: &[String]) -> Result<Vec<Isbn>, InputError> {
+    let scans = scan_images(paths).map_err(|e| InputError::ImagesError(e.to_string()))?;
+    let mut list: Vec<Isbn> = vec![];
+
+    for scan in scans.iter() {
+        eprintln!("{}", describe_scan(scan));
+        for isbn in scan.isbns.iter() {
+            if !list.contains(isbn) {
+                list.push(isbn.clone());
+            }
+        }
+    }
+
+    Ok(list)
+}
+
+/// One line report of an image scan
+///
+fn describe_scan(scan: &ImageScan) -> String {
+    let name = scan.path.display();
+    if let Some(error) = &scan.error {
+        return format!("{}: cannot decode image: {}", name, error);
+    }
+
+    let mut found: Vec<String> = scan.isbns.iter().map(|isbn| isbn.to_string()).collect();
+    if found.is_empty() {
+        found.push("no ISBN barcode found".to_string());
+    }
+    if !scan.ignored.is_empty() {
+        found.push(format!(
+            "ignored non-book codes {}",
+            scan.ignored.join(", ")
+        ));
+    }
+
+    format!("{}: {}", name, found.join(", "))
+}
+
+/// Read a file to string
+///
+fn read_file(input_file: &str) -> IoResult<String> {
+    let mut f = File::open(input_file)?;
+    let mut buf = String::new();
+
+    f.read_to_string(&mut buf)?;
+    Ok(buf)
+}
+
+/// Returns true if stdin data is set
+///
+fn has_stream_data() -> bool {
+    !is(Stream::Stdin)
+}
+
+/// Returns true if stdin is attached to a terminal
+///
+pub fn has_terminal_stdin() -> bool {
+    is(Stream::Stdin)
+}
+
+/// Returns true if stderr is attached to a terminal
+///
+pub fn has_terminal_stderr() -> bool {
+    is(Stream::Stderr)
+}
+
+/// Read the whole stdin in a string
+///
+fn read_input_stream() -> IoResult<String> {
+    let mut input = String::new();
+    stdin().read_to_string(&mut input)?;
+    Ok(input.trim().to_string())
+}
@@ -0,0 +1,88 @@
+use crate::cli::Args;
+use crate::progress;
+use std::fs::OpenOptions;
+use std::io::{stderr, Result as IoResult, Write};
+use std::sync::Mutex;
+use thiserror::Error;
+use tracing::metadata::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::prelude::*;
+
+/// Writes the logs to stderr, hiding the progress bar meanwhile
+///
+struct StderrWriter;
+
+/// Logging setup error
+///
+#[derive(Error, Debug)]
+pub enum LogError {
+    #[error("Cannot open log file \"{0}\": {1}")]
+    LogFileError(String, String),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Install the global log subscriber.
+///
+/// Verbosity is taken from the CLI: warnings only by default,
+/// `-v` info, `-vv` debug, `-vvv` trace and `-q` silences everything.
+/// Logs are written on stderr unless a log file is provided, so they
+/// never mix with the command output.
+///
+pub fn init(args: &Args) -> Result<(), LogError> {
+    let level = level(args.verbose, args.quiet);
+    let filter = Targets::new()
+        .with_target(env!("CARGO_PKG_NAME"), level)
+        .with_default(level.min(LevelFilter::WARN));
+
+    let layer = tracing_subscriber::fmt::layer().with_target(false);
+
+    match &args.log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(path)
+                .map_err(|e| LogError::LogFileError(path.clone(), e.to_string()))?;
+            let layer = layer.with_ansi(false).with_writer(Mutex::new(file));
+            tracing_subscriber::registry()
+                .with(layer.with_filter(filter))
+                .init();
+        }
+        None => {
+            let layer = layer.with_writer(|| StderrWriter);
+            tracing_subscriber::registry()
+                .with(layer.with_filter(filter))
+                .init();
+        }
+    };
+
+    Ok(())
+}
+
+impl Write for StderrWriter {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        progress::suspend(|| stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        stderr().flush()
+    }
+}
+
+/// Map the CLI verbosity flags to a level filter.
+///
+fn level(verbose: u64, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::OFF;
+    }
+
+    match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
@@ -2,69 +2,1196 @@ extern crate exitcode;
 
 mod app;
 mod books;
+mod calendar;
 mod cli;
 mod config;
+mod html;
 mod input;
+mod logging;
+mod progress;
+mod report;
 
-use app::{FetchCommand, Stats};
+use app::{
+    AuthorCommand, CacheCommand, CommandError, CopyCommand, EbookStats, EbooksCommand,
+    EditCommand, FetchCommand, LabelCommand, LabelKind, LabelStats, ListCommand,
+    LoanCommand, LookupCommand, LookupOutcome, ReadingCommand, RefreshCommand,
+    ScanCommand, ScanStats, SeriesCommand, Stats, StatsCommand, WishCommand, WorkCommand,
+};
+use books::google::{FetchError, HttpError};
+use books::{
+    Filter, Holding, HoldingError, Isbn, LibraryStats, Loan, LoanError, Outcome,
+    OutcomeStatus, Override, OverrideError, Reading, ReadingError, ReadingUpdate,
+    StorageError, Tally, Volume, Wish, WishError, WorkError,
+};
 use clap::Parser;
-use cli::{Args, BuildError, ConfigBuilder};
+use cli::{
+    Args, AuthorAction, BuildError, CacheAction, Command, ConfigBuilder, CopyAction,
+    CopyArgs, FilterArgs, LabelAction, ReadingArgs, StatsFormat, WishAction,
+};
+use config::Config;
 use input::{InputError, InputReader};
+use logging::LogError;
+use progress::Progress;
+use report::{Report, ReportWriter};
 use std::error::Error as StdError;
+use std::io::{stderr, stdout, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Instant, SystemTime};
 use thiserror::Error;
 
+/// Some ISBN were fetched, some others could not be resolved
+///
+const PARTIAL: i32 = 3;
+
+/// None of the ISBN to fetch could be resolved
+///
+const UNRESOLVED: i32 = 4;
+
 /// Fetches input ISBN and save the resulted book in a JSON library file.
 ///
+/// Exit codes:
+///
+/// - 0: success, including nothing to fetch
+/// - 3: partial success, some ISBN unresolved
+/// - 4: no ISBN could be resolved
+/// - 66: input error
+/// - 69: network error, API unavailable or quota exceeded
+/// - 74: storage error
+/// - 77: authentication error
+/// - 78: configuration error
+///
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn StdError>> {
     let cli = Args::parse();
-    let res = print(run(&cli).await);
 
+    let code = match &cli.command {
+        None => fetch(&cli).await,
+        Some(Command::Scan) => scan(&cli).await,
+        Some(Command::ScanEbooks { dir, enrich }) => {
+            scan_ebooks(&cli, dir, *enrich).await
+        }
+        Some(Command::Lookup {
+            title,
+            author,
+            accept_above,
+        }) => lookup(&cli, title, author.as_deref(), *accept_above).await,
+        Some(Command::Cache { action }) => cache(&cli, action),
+        Some(Command::Tag { action }) => label(&cli, LabelKind::Tag, action),
+        Some(Command::Shelf { action }) => label(&cli, LabelKind::Shelf, action),
+        Some(Command::List { filter }) => list(&cli, to_filter(filter, None)),
+        Some(Command::Search { text, filter }) => {
+            list(&cli, to_filter(filter, Some(text)))
+        }
+        Some(Command::Reading { isbn, update }) => {
+            reading(&cli, &Isbn::new(isbn), &to_update(update))
+        }
+        Some(Command::Lend {
+            isbn,
+            to,
+            due,
+            copy,
+        }) => lend(&cli, &Isbn::new(isbn), to, due.as_deref(), copy.as_deref()),
+        Some(Command::Return { isbn, copy }) => {
+            give_back(&cli, &Isbn::new(isbn), copy.as_deref())
+        }
+        Some(Command::Authors { action }) => authors(&cli, action.as_ref()),
+        Some(Command::Copy { action }) => copy(&cli, action).await,
+        Some(Command::Wish { action }) => wish(&cli, action).await,
+        Some(Command::Loans { isbn, ics }) => loans(
+            &cli,
+            isbn.as_deref().map(Isbn::new).as_ref(),
+            ics.as_deref(),
+        ),
+        Some(Command::Overdue) => overdue(&cli),
+        Some(Command::Edit { isbn, set, unset }) => {
+            edit(&cli, &Isbn::new(isbn), &to_assignments(set), unset)
+        }
+        Some(Command::Overrides) => overrides(&cli),
+        Some(Command::Works { min_score }) => works(&cli, *min_score),
+        Some(Command::Series) => series(&cli),
+        Some(Command::Stats { format, html, top }) => {
+            stats(&cli, format, html.as_deref(), *top)
+        }
+        Some(Command::Duplicates { min_score }) => duplicates(&cli, *min_score),
+        Some(Command::Merge { isbns }) => merge(&cli, &to_isbns(isbns)),
+        Some(Command::Unmerge { isbn }) => unmerge(&cli, &Isbn::new(isbn)),
+        Some(Command::Refresh { isbns }) => refresh(&cli, &to_isbns(isbns)).await,
+    };
+
+    std::process::exit(code);
+}
+
+// Setup logging and load the configuration
+//
+fn setup(cli: &Args) -> Result<Config, AppError> {
+    logging::init(cli)?;
+    let config = ConfigBuilder::from_cli_args(cli)?;
+
+    Ok(config)
+}
+
+// Print the error of a command, if any, and return its exit code
+//
+fn exit_with<T>(res: Result<T, AppError>) -> i32 {
     match res {
-        Ok(_) => std::process::exit(exitcode::OK),
-        Err(_) => std::process::exit(exitcode::IOERR),
+        Ok(_) => exitcode::OK,
+        Err(e) => fail(e),
+    }
+}
+
+// Print the error of a command and return its exit code
+//
+fn fail(e: AppError) -> i32 {
+    eprintln!("ERROR! {0}", e);
+    e.exit_code()
+}
+
+// Fetch the input ISBN, write the report and print the summary
+//
+async fn fetch(cli: &Args) -> i32 {
+    let started = (SystemTime::now(), Instant::now());
+    let (input, res) = run(cli).await;
+
+    let reporter = ReportWriter::from_cli_args(cli);
+    if let Some(writer) = &reporter {
+        let failure = res.as_ref().ok().and_then(AppError::from_stats);
+        let error = res.as_ref().err().or(failure.as_ref());
+        let report = Report::new(
+            started.0,
+            started.1.elapsed(),
+            &input,
+            res.as_ref().ok(),
+            error,
+        );
+        if let Err(e) = writer.write(&report) {
+            eprintln!("ERROR! Cannot write report: {0}", e);
+            return exitcode::IOERR;
+        }
+    }
+
+    let to_stderr = reporter.is_some_and(|writer| writer.uses_stdout());
+    let res = print(res, to_stderr);
+
+    match res {
+        Ok(_) => exitcode::OK,
+        Err(e) => e.exit_code(),
+    }
+}
+
+// Read codes from a barcode scanner until interrupted
+//
+async fn scan(cli: &Args) -> i32 {
+    let res = match setup(cli) {
+        Ok(config) => match ScanCommand::create(&config) {
+            Ok(mut command) => {
+                eprintln!("Scan the barcodes, Ctrl-C or Ctrl-D to stop.");
+                command.run().await.map_err(AppError::from)
+            }
+            Err(e) => Err(AppError::from(e)),
+        },
+        Err(e) => Err(e),
+    };
+
+    exit_with(res.map(|stats| print_scan(&stats)))
+}
+
+// Build library entries from a folder of EPUB files
+//
+async fn scan_ebooks(cli: &Args, dir: &str, enrich: bool) -> i32 {
+    let res = match setup(cli) {
+        Ok(config) => match EbooksCommand::create(&config) {
+            Ok(mut command) => command
+                .run(Path::new(dir), enrich)
+                .await
+                .map_err(AppError::from),
+            Err(e) => Err(AppError::from(e)),
+        },
+        Err(e) => Err(e),
+    };
+
+    let res = res.and_then(|stats| {
+        print_ebooks(&stats);
+        match (stats.added, stats.failed) {
+            (_, 0) => Ok(stats),
+            (0, failed) => Err(AppError::Unresolved(failed)),
+            (_, failed) => Err(AppError::PartialFailure(
+                failed,
+                stats.found - stats.skipped,
+            )),
+        }
+    });
+
+    exit_with(res)
+}
+
+// Search a book by title and author and store the chosen candidate
+//
+async fn lookup(
+    cli: &Args,
+    title: &str,
+    author: Option<&str>,
+    accept_above: Option<f64>,
+) -> i32 {
+    let res = match setup(cli) {
+        Ok(config) => match LookupCommand::create(&config) {
+            Ok(mut command) => command
+                .run(title, author, accept_above)
+                .await
+                .map_err(AppError::from),
+            Err(e) => Err(AppError::from(e)),
+        },
+        Err(e) => Err(e),
+    };
+
+    let res = res.and_then(|outcome| match outcome {
+        LookupOutcome::Added(volume) => {
+            println!("Added {}: {}", volume.isbn, volume.title);
+            Ok(())
+        }
+        LookupOutcome::Duplicate(volume) => {
+            println!("Already in the library {}: {}", volume.isbn, volume.title);
+            Ok(())
+        }
+        LookupOutcome::NoCandidates => {
+            println!("No book found.");
+            Err(AppError::Unresolved(1))
+        }
+        LookupOutcome::NotSelected => {
+            println!("No book selected.");
+            Err(AppError::Unresolved(1))
+        }
+    });
+
+    exit_with(res)
+}
+
+// Show or clean the API response cache
+//
+fn cache(cli: &Args, action: &CacheAction) -> i32 {
+    let command = match setup(cli) {
+        Ok(config) => CacheCommand::create(&config),
+        Err(e) => return fail(e),
+    };
+
+    let res = match action {
+        CacheAction::Stats => command.stats().map(|stats| {
+            println!("Cache folder: {0}", command.dir().display());
+            println!("Cached responses: {0}", stats.entries);
+            println!("Expired: {0}", stats.expired);
+            println!("Size: {0} KiB", stats.bytes.div_ceil(1024));
+        }),
+        CacheAction::Clear => command
+            .clear()
+            .map(|removed| println!("Removed responses: {0}", removed)),
+        CacheAction::Prune => command
+            .prune()
+            .map(|removed| println!("Removed expired responses: {0}", removed)),
+    };
+
+    exit_with(res.map_err(|e| {
+        AppError::StorageError(format!("{}: {}", command.dir().display(), e))
+    }))
+}
+
+// Add or remove a tag or a shelf, or list them
+//
+fn label(cli: &Args, kind: LabelKind, action: &LabelAction) -> i32 {
+    let mut command = match setup(cli)
+        .and_then(|config| LabelCommand::create(&config, kind).map_err(AppError::from))
+    {
+        Ok(command) => command,
+        Err(e) => return fail(e),
+    };
+
+    let res = match action {
+        LabelAction::Add { name, isbns } => command.add(name, &to_isbns(isbns)),
+        LabelAction::Remove { name, isbns } => command.remove(name, &to_isbns(isbns)),
+        LabelAction::List => {
+            for (name, count) in command.counts() {
+                println!("{0} ({1})", name, count);
+            }
+            return exitcode::OK;
+        }
+    };
+
+    let res = res.map_err(AppError::from).and_then(|stats| {
+        print_labels(&stats);
+        match (stats.changed, stats.missing.len()) {
+            (_, 0) => Ok(stats),
+            (0, missing) => Err(AppError::Unresolved(missing)),
+            (changed, missing) => {
+                Err(AppError::PartialFailure(missing, changed + missing))
+            }
+        }
+    });
+
+    exit_with(res)
+}
+
+// List the library books matching the filter
+//
+fn list(cli: &Args, filter: Filter) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| ListCommand::create(&config).map_err(AppError::from));
+
+    exit_with(res.map(|command| {
+        if command.run(&filter) == 0 {
+            eprintln!("No books found.");
+        }
+    }))
+}
+
+// Show the reading log of a book, updating it first when
+// some change is given
+//
+fn reading(cli: &Args, isbn: &Isbn, update: &ReadingUpdate) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| ReadingCommand::create(&config).map_err(AppError::from))
+        .and_then(|mut command| match update.is_empty() {
+            true => command
+                .show(isbn)
+                .ok_or_else(|| AppError::from(ReadingError::NotInLibrary(isbn.clone()))),
+            false => command.update(isbn, update).map_err(AppError::from),
+        });
+
+    exit_with(res.map(|reading| print_reading(&reading)))
+}
+
+// Lend a book to someone
+//
+fn lend(
+    cli: &Args,
+    isbn: &Isbn,
+    borrower: &str,
+    due: Option<&str>,
+    copy: Option<&str>,
+) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| LoanCommand::create(&config).map_err(AppError::from))
+        .and_then(|mut command| {
+            command
+                .lend(isbn, borrower, due, copy)
+                .map_err(AppError::from)
+        });
+
+    exit_with(res.map(|loan| {
+        print!("Lent {0}", isbn);
+        if let Some(copy) = &loan.copy {
+            print!(" (copy {0})", copy);
+        }
+        print!(" to {0}", loan.borrower);
+        match &loan.due {
+            Some(due) => println!(", due {0}", due),
+            None => println!(),
+        }
+    }))
+}
+
+// Mark a lent book as returned
+//
+fn give_back(cli: &Args, isbn: &Isbn, copy: Option<&str>) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| LoanCommand::create(&config).map_err(AppError::from))
+        .and_then(|mut command| command.give_back(isbn, copy).map_err(AppError::from));
+
+    exit_with(res.map(|loan| {
+        print!("Returned {0}", isbn);
+        if let Some(copy) = &loan.copy {
+            print!(" (copy {0})", copy);
+        }
+        println!(" by {0}", loan.borrower);
+    }))
+}
+
+// List the authors with their books, or manage the author aliases
+//
+fn authors(cli: &Args, action: Option<&AuthorAction>) -> i32 {
+    let mut command = match setup(cli)
+        .and_then(|config| AuthorCommand::create(&config).map_err(AppError::from))
+    {
+        Ok(command) => command,
+        Err(e) => return fail(e),
+    };
+
+    let res = match action {
+        None => {
+            let index = command.index();
+            for author in index.iter() {
+                println!("{0} ({1})", author.name, author.volumes.len());
+                for volume in author.volumes.iter() {
+                    println!("    {0}  {1}", volume.isbn, volume.title);
+                }
+            }
+            if index.is_empty() {
+                eprintln!("No authors found.");
+            }
+            Ok(())
+        }
+        Some(AuthorAction::Alias { alias, name }) => command
+            .alias(alias, name)
+            .map(|_| println!("{0} stands for {1}", alias, name))
+            .map_err(AppError::from),
+        Some(AuthorAction::Unalias { alias }) => match command.unalias(alias) {
+            Ok(true) => {
+                println!("Removed alias {0}", alias);
+                Ok(())
+            }
+            Ok(false) => Err(AppError::InputError(format!("No alias {}", alias))),
+            Err(e) => Err(AppError::from(e)),
+        },
+        Some(AuthorAction::Aliases) => {
+            for (alias, name) in command.aliases() {
+                println!("{0} -> {1}", alias, name);
+            }
+            Ok(())
+        }
+    };
+
+    exit_with(res)
+}
+
+// Add, change, remove or list the physical copies of the books
+//
+async fn copy(cli: &Args, action: &CopyAction) -> i32 {
+    let mut command = match setup(cli)
+        .and_then(|config| CopyCommand::create(&config).map_err(AppError::from))
+    {
+        Ok(command) => command,
+        Err(e) => return fail(e),
+    };
+
+    let res = match action {
+        CopyAction::Add { isbn, id, details } => {
+            let holding = Holding {
+                id: id.clone().unwrap_or_default(),
+                ..to_holding(details)
+            };
+            command
+                .add(&Isbn::new(isbn), holding)
+                .await
+                .map(|(holding, volume)| {
+                    println!(
+                        "Added copy {0} of {1}: {2}",
+                        holding.id, volume.isbn, volume.title
+                    )
+                })
+        }
+        CopyAction::Update { id, details } => {
+            let changes = Holding {
+                id: id.clone(),
+                ..to_holding(details)
+            };
+            command
+                .update(&changes)
+                .map(|holding| println!("{0}", describe_holding(&holding)))
+        }
+        CopyAction::Remove { id } => command
+            .remove(id)
+            .map(|isbn| println!("Removed copy {0} of {1}", id, isbn)),
+        CopyAction::List { isbn } => {
+            let isbn = isbn.as_deref().map(Isbn::new);
+            let copies = command.list(isbn.as_ref());
+            for (volume, holding) in copies.iter() {
+                println!("{0}  {1}", describe_holding(holding), volume.title);
+            }
+            if copies.is_empty() {
+                eprintln!("No copies found.");
+            }
+            Ok(())
+        }
+    };
+    let res = res.map_err(AppError::from);
+
+    exit_with(res)
+}
+
+// Manage the wishlist
+//
+async fn wish(cli: &Args, action: &WishAction) -> i32 {
+    let mut command = match setup(cli)
+        .and_then(|config| WishCommand::create(&config).map_err(AppError::from))
+    {
+        Ok(command) => command,
+        Err(e) => return fail(e),
+    };
+
+    let res = match action {
+        WishAction::Add { isbn, note } => command
+            .add(&Isbn::new(isbn), note.clone())
+            .await
+            .map(|(wish, added)| match added {
+                true => println!("Wished {0}: {1}", wish.volume.isbn, wish.volume.title),
+                false => println!(
+                    "Already wished {0}: {1}",
+                    wish.volume.isbn, wish.volume.title
+                ),
+            }),
+        WishAction::Acquire { isbn, id, details } => {
+            let holding = Holding {
+                id: id.clone().unwrap_or_default(),
+                ..to_holding(details)
+            };
+            command
+                .acquire(&Isbn::new(isbn), holding)
+                .map(|holding| println!("Acquired {0}", describe_holding(&holding)))
+        }
+        WishAction::Remove { isbn } => command.remove(&Isbn::new(isbn)).map(|wish| {
+            println!(
+                "Removed {0} from the wishlist: {1}",
+                wish.volume.isbn, wish.volume.title
+            )
+        }),
+        WishAction::List => {
+            let wishes = command.list();
+            for wish in wishes.iter() {
+                println!("{0}", describe_wish(wish));
+            }
+            if wishes.is_empty() {
+                eprintln!("The wishlist is empty.");
+            }
+            Ok(())
+        }
+    };
+    let res = res.map_err(AppError::from);
+
+    exit_with(res)
+}
+
+// Show the books out on loan, or the loan history of a book,
+// optionally exporting the due dates as iCalendar
+//
+fn loans(cli: &Args, isbn: Option<&Isbn>, ics: Option<&str>) -> i32 {
+    let command = match setup(cli)
+        .and_then(|config| LoanCommand::create(&config).map_err(AppError::from))
+    {
+        Ok(command) => command,
+        Err(e) => return fail(e),
+    };
+
+    let res = match (isbn, ics) {
+        (Some(isbn), _) => command
+            .history(isbn)
+            .map(|history| {
+                for loan in history.iter() {
+                    println!("{0}", describe_loan(loan));
+                }
+                if history.is_empty() {
+                    eprintln!("Never lent.");
+                }
+            })
+            .map_err(AppError::from),
+        (None, Some(path)) => command
+            .export(path)
+            .map(|count| println!("Due dates exported: {0}", count))
+            .map_err(|e| AppError::StorageError(format!("{}: {}", path, e))),
+        (None, None) => {
+            print_loans(&command.lent());
+            Ok(())
+        }
+    };
+
+    exit_with(res)
+}
+
+// Show the books out on loan after their due date
+//
+fn overdue(cli: &Args) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| LoanCommand::create(&config).map_err(AppError::from));
+
+    exit_with(res.map(|command| print_loans(&command.overdue())))
+}
+
+// Set or drop fields of a book by hand
+//
+fn edit(cli: &Args, isbn: &Isbn, set: &[(String, String)], unset: &[String]) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| EditCommand::create(&config).map_err(AppError::from))
+        .and_then(|mut command| command.run(isbn, set, unset).map_err(AppError::from));
+
+    exit_with(res.map(|overrides| print_overrides(&overrides)))
+}
+
+// Show the fields set by hand on the library books
+//
+fn overrides(cli: &Args) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| EditCommand::create(&config).map_err(AppError::from));
+
+    exit_with(res.map(|command| print_overrides(&command.list())))
+}
+
+// Show the works with many editions
+//
+fn works(cli: &Args, min_score: f64) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| WorkCommand::create(&config).map_err(AppError::from));
+
+    exit_with(res.map(|command| {
+        let works = command.works(min_score);
+        for work in works.iter() {
+            println!("{0} ({1} editions)", work.title, work.editions.len());
+            for volume in work.editions.iter() {
+                println!("    {0}", describe_edition(volume));
+            }
+        }
+        if works.is_empty() {
+            eprintln!("No works with many editions.");
+        }
+    }))
+}
+
+// Show the series in reading order with the missing volumes
+//
+fn series(cli: &Args) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| SeriesCommand::create(&config).map_err(AppError::from));
+
+    exit_with(res.map(|command| {
+        let series = command.list();
+        for entry in series.iter() {
+            println!("{0} ({1} volumes)", entry.name, entry.volumes.len());
+            for volume in entry.volumes.iter() {
+                let index = volume
+                    .series_index
+                    .map(|index| index.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                println!("    {0:>3}  {1}  {2}", index, volume.isbn, volume.title);
+            }
+            if !entry.missing.is_empty() {
+                let missing: Vec<String> =
+                    entry.missing.iter().map(|n| n.to_string()).collect();
+                println!("    missing: {0}", missing.join(", "));
+            }
+        }
+        if series.is_empty() {
+            eprintln!("No series in the library.");
+        }
+    }))
+}
+
+// Show the library statistics, or write them as an HTML report
+//
+fn stats(cli: &Args, format: &StatsFormat, html: Option<&str>, top: usize) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| StatsCommand::create(&config).map_err(AppError::from))
+        .and_then(|command| match html {
+            Some(path) => command
+                .export(path, top)
+                .map(|_| println!("Statistics written to {0}", path))
+                .map_err(|e| AppError::StorageError(format!("{}: {}", path, e))),
+            None => {
+                let stats = command.stats();
+                match format {
+                    StatsFormat::Table => print_stats(&stats, top),
+                    StatsFormat::Json => println!(
+                        "{0}",
+                        serde_json::to_string_pretty(&stats).unwrap_or_default()
+                    ),
+                }
+                Ok(())
+            }
+        });
+
+    exit_with(res)
+}
+
+// Show the likely duplicates with their score
+//
+fn duplicates(cli: &Args, min_score: f64) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| WorkCommand::create(&config).map_err(AppError::from));
+
+    exit_with(res.map(|command| {
+        let duplicates = command.duplicates(min_score);
+        for duplicate in duplicates.iter() {
+            println!("[{0:.2}]", duplicate.score);
+            println!("    {0}", describe_edition(&duplicate.first));
+            println!("    {0}", describe_edition(&duplicate.second));
+        }
+        if duplicates.is_empty() {
+            eprintln!("No duplicates found.");
+        }
+    }))
+}
+
+// Fold books into one work
+//
+fn merge(cli: &Args, isbns: &[Isbn]) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| WorkCommand::create(&config).map_err(AppError::from))
+        .and_then(|mut command| command.merge(isbns).map_err(AppError::from));
+
+    exit_with(res.map(|count| println!("Editions of the work: {0}", count)))
+}
+
+// Take a book out of its work
+//
+fn unmerge(cli: &Args, isbn: &Isbn) -> i32 {
+    let res = setup(cli)
+        .and_then(|config| WorkCommand::create(&config).map_err(AppError::from))
+        .and_then(|mut command| command.unmerge(isbn).map_err(AppError::from));
+
+    exit_with(res.map(|_| println!("Took {0} out of its work", isbn)))
+}
+
+// Fetch again the metadata of the library books
+//
+async fn refresh(cli: &Args, isbns: &[Isbn]) -> i32 {
+    let res = match setup(cli) {
+        Ok(config) => match RefreshCommand::create(&config) {
+            Ok(mut command) => {
+                if let Some(progress) = Progress::from_cli_args(cli) {
+                    command.observe(Rc::new(progress));
+                }
+                command.run(isbns).await.map_err(AppError::from)
+            }
+            Err(e) => Err(AppError::from(e)),
+        },
+        Err(e) => Err(e),
     };
+
+    let res = res.and_then(|outcomes| {
+        let (refreshed, failed) = print_refresh(&outcomes);
+        match (refreshed, failed) {
+            (_, 0) => Ok(()),
+            (0, failed) => Err(AppError::Unresolved(failed)),
+            (refreshed, failed) => {
+                Err(AppError::PartialFailure(failed, refreshed + failed))
+            }
+        }
+    });
+
+    exit_with(res)
 }
 
 // Run the application and manage the app result
 //
-async fn run(cli: &Args) -> Result<Stats, AppError> {
-    let config = ConfigBuilder::from_cli_args(&cli)?;
-    let isbns = InputReader::read(&cli)?;
+async fn run(cli: &Args) -> (Vec<Isbn>, Result<Stats, AppError>) {
+    let config = match setup(cli) {
+        Ok(config) => config,
+        Err(e) => return (vec![], Err(e)),
+    };
+    let input = match InputReader::read(cli) {
+        Ok(input) => input,
+        Err(e) => return (vec![], Err(AppError::from(e))),
+    };
 
-    let mut command = FetchCommand::create(&config);
-    let res = command.run(&isbns).await?;
+    let res = match FetchCommand::create(&config) {
+        Ok(mut command) => {
+            if let Some(progress) = Progress::from_cli_args(cli) {
+                command.observe(Rc::new(progress));
+            }
+            command.run(&input).await.map_err(AppError::from)
+        }
+        Err(e) => Err(AppError::from(e)),
+    };
 
-    Ok(res)
+    (input.isbns, res)
 }
 
 // Print output
 //
-fn print(res: Result<Stats, AppError>) -> Result<Stats, AppError> {
-    match res {
-        Ok(stats) => {
-            println!("Successfully completed!");
-            println!("Provided ISBN: {0}", stats.input_list);
-            println!("Fetched Volumes: {0}", stats.new_volumes);
-            Ok(stats)
+// The summary goes to stderr when stdout is taken by the report.
+//
+fn print(res: Result<Stats, AppError>, to_stderr: bool) -> Result<Stats, AppError> {
+    let mut out: Box<dyn Write> = match to_stderr {
+        true => Box::new(stderr()),
+        false => Box::new(stdout()),
+    };
+
+    let res = res.and_then(|stats| {
+        let failure = AppError::from_stats(&stats);
+        let unresolved = failed(&stats);
+
+        let _ = match failure {
+            None => writeln!(out, "Successfully completed!"),
+            Some(_) => writeln!(out, "Completed with errors."),
+        };
+        let _ = writeln!(out, "Provided ISBN: {0}", stats.input.len());
+        let low_confidence = stats.low_confidence.len();
+        let _ = writeln!(
+            out,
+            "Fetched Volumes: {0}",
+            stats.new_volumes - low_confidence
+        );
+        if low_confidence > 0 {
+            let _ = writeln!(out, "Low confidence matches: {0}", low_confidence);
+        }
+        if stats.requests > 0 {
+            let _ = writeln!(
+                out,
+                "API requests: {0} ({1} saved)",
+                stats.requests, stats.requests_saved
+            );
         }
-        Err(e) => {
-            eprintln!("ERROR! {0}", e);
-            Err(e)
+        if unresolved > 0 {
+            let _ = writeln!(out, "Unresolved ISBN: {0}", unresolved);
+        }
+
+        failure.map_or(Ok(stats), Err)
+    });
+
+    if let Err(e) = &res {
+        eprintln!("ERROR! {0}", e);
+    }
+    res
+}
+
+// Print the scan session summary
+//
+fn print_scan(stats: &ScanStats) {
+    println!("Scanned codes: {0}", stats.scanned);
+    println!("Added Volumes: {0}", stats.added);
+    println!("Duplicates: {0}", stats.duplicates);
+    println!("Failed: {0}", stats.failed);
+}
+
+// Print the ebook scan summary
+//
+fn print_ebooks(stats: &EbookStats) {
+    println!("EPUB found: {0}", stats.found);
+    println!("Added Volumes: {0}", stats.added);
+    println!("Skipped: {0}", stats.skipped);
+    println!("Failed: {0}", stats.failed);
+}
+
+// Print the label change summary
+//
+fn print_labels(stats: &LabelStats) {
+    for isbn in stats.missing.iter() {
+        eprintln!("Warning: {0} is not in the library", isbn);
+    }
+    println!("Updated books: {0}", stats.changed);
+}
+
+// Print a reading log, one field per line
+//
+fn print_reading(reading: &Reading) {
+    if let Some(status) = reading.status {
+        println!("Status: {0}", status);
+    }
+    if let Some(rating) = reading.rating {
+        println!("Rating: {0}/5", rating);
+    }
+    if let Some(started) = &reading.started {
+        println!("Started: {0}", started);
+    }
+    if let Some(finished) = &reading.finished {
+        println!("Finished: {0}", finished);
+    }
+    if let Some(notes) = &reading.notes {
+        println!("Notes: {0}", notes);
+    }
+    for quote in reading.quotes.iter() {
+        println!("Quote: \"{0}\"", quote);
+    }
+    if reading == &Reading::default() {
+        println!("Nothing logged yet.");
+    }
+}
+
+// Print the lent books, one per line
+//
+fn print_loans(loans: &[(Volume, &Loan)]) {
+    for (volume, loan) in loans.iter() {
+        println!(
+            "{0}  {1} - {2}",
+            volume.isbn,
+            volume.title,
+            describe_loan(loan)
+        );
+    }
+    if loans.is_empty() {
+        eprintln!("No books found.");
+    }
+}
+
+// One line description of a loan: borrower, copy and dates
+//
+fn describe_loan(loan: &Loan) -> String {
+    let mut line = format!("lent to {} on {}", loan.borrower, loan.lent);
+    if let Some(copy) = &loan.copy {
+        line.push_str(&format!(", copy {}", copy));
+    }
+    if let Some(due) = &loan.due {
+        line.push_str(&format!(", due {}", due));
+    }
+    if let Some(returned) = &loan.returned {
+        line.push_str(&format!(", returned {}", returned));
+    }
+    line
+}
+
+// One line description of a copy: id and details
+//
+fn describe_holding(holding: &Holding) -> String {
+    let details: Vec<&str> = [
+        &holding.location,
+        &holding.condition,
+        &holding.acquired,
+        &holding.price,
+    ]
+    .iter()
+    .filter_map(|detail| detail.as_deref())
+    .collect();
+
+    match details.is_empty() {
+        true => holding.id.clone(),
+        false => format!("{}  [{}]", holding.id, details.join(", ")),
+    }
+}
+
+// Print the statistics as tables, the publishers and authors
+// limited to the most frequent
+//
+fn print_stats(stats: &LibraryStats, top: usize) {
+    println!("Volumes  {0}", stats.volumes);
+    println!("Pages    {0}", stats.pages);
+    println!("Authors  {0}", stats.authors);
+
+    let total = stats.volumes;
+    print_tallies("Languages", &stats.languages, total, usize::MAX);
+    print_tallies("Publishers", &stats.publishers, total, top);
+    print_tallies("Decades", &stats.decades, total, usize::MAX);
+    print_tallies("Authors", &stats.by_author, total, top);
+    print_tallies("Added per month", &stats.added, total, usize::MAX);
+    print_tallies(
+        "Metadata completeness",
+        &stats.completeness,
+        total,
+        usize::MAX,
+    );
+}
+
+// Print a table of tallies with their share of the books
+//
+fn print_tallies(title: &str, tallies: &[Tally], total: usize, top: usize) {
+    let shown = &tallies[..tallies.len().min(top)];
+    let width = shown
+        .iter()
+        .map(|tally| tally.label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    println!();
+    println!("{0}", title);
+    for tally in shown.iter() {
+        println!(
+            "  {0:<width$}  {1:>5}  {2:>3}%",
+            tally.label,
+            tally.count,
+            tally.count * 100 / total.max(1),
+            width = width
+        );
+    }
+    if tallies.len() > shown.len() {
+        println!("  ({0} more)", tallies.len() - shown.len());
+    }
+}
+
+// Describe a wished book with the date it was wished
+//
+fn describe_wish(wish: &Wish) -> String {
+    let line = format!(
+        "{0}  {1}  {2}",
+        wish.added, wish.volume.isbn, wish.volume.title
+    );
+    match &wish.note {
+        Some(note) => format!("{}  ({})", line, note),
+        None => line,
+    }
+}
+
+// Print the fields set by hand with the provider values they hide
+//
+fn print_overrides(overrides: &[Override]) {
+    for entry in overrides.iter() {
+        println!(
+            "{0}  {1} = \"{2}\" (provider: \"{3}\")",
+            entry.isbn, entry.field, entry.value, entry.provider
+        );
+    }
+    if overrides.is_empty() {
+        eprintln!("No fields set by hand.");
+    }
+}
+
+// One line description of an edition: ISBN, title,
+// publisher, date and language
+//
+fn describe_edition(volume: &Volume) -> String {
+    let details: Vec<&str> = [
+        volume.publisher.as_str(),
+        volume.published_date.as_str(),
+        volume.language.as_str(),
+    ]
+    .iter()
+    .copied()
+    .filter(|detail| !detail.is_empty())
+    .collect();
+
+    match details.is_empty() {
+        true => format!("{}  {}", volume.isbn, volume.title),
+        false => format!("{}  {} ({})", volume.isbn, volume.title, details.join(", ")),
+    }
+}
+
+// Print the refresh summary, returning the count
+// of the books refreshed and failed
+//
+fn print_refresh(outcomes: &[Outcome]) -> (usize, usize) {
+    let (mut refreshed, mut skipped, mut failed) = (0, 0, 0);
+    for outcome in outcomes.iter() {
+        match &outcome.status {
+            OutcomeStatus::Fetched => refreshed += 1,
+            OutcomeStatus::Skipped => {
+                eprintln!("Warning: {0} skipped", outcome.isbn);
+                skipped += 1;
+            }
+            OutcomeStatus::Failed(e) => {
+                eprintln!("Warning: {0} not refreshed: {1}", outcome.isbn, e);
+                failed += 1;
+            }
         }
     }
+    println!("Refreshed Volumes: {0}", refreshed);
+    println!("Skipped: {0}", skipped);
+    println!("Failed: {0}", failed);
+    (refreshed, failed)
+}
+
+// Map the filter options to the library filter
+//
+fn to_filter(args: &FilterArgs, text: Option<&str>) -> Filter {
+    Filter {
+        tags: args.tags.clone(),
+        shelf: args.shelf.clone(),
+        text: text.map(|text| text.to_string()),
+        status: args.status,
+    }
+}
+
+// Split the FIELD=VALUE arguments
+//
+fn to_assignments(list: &[String]) -> Vec<(String, String)> {
+    list.iter()
+        .filter_map(|item| item.split_once('='))
+        .map(|(field, value)| (field.trim().to_string(), value.to_string()))
+        .collect()
+}
+
+// Map the copy options to the holding details
+//
+fn to_holding(args: &CopyArgs) -> Holding {
+    Holding {
+        id: String::new(),
+        location: args.location.clone(),
+        condition: args.condition.clone(),
+        acquired: args.acquired.clone(),
+        price: args.price.clone(),
+    }
+}
+
+// Map the reading options to the log changes
+//
+fn to_update(args: &ReadingArgs) -> ReadingUpdate {
+    ReadingUpdate {
+        status: args.status,
+        rating: args.rating,
+        started: args.started.clone(),
+        finished: args.finished.clone(),
+        notes: args.notes.clone(),
+        quotes: args.quotes.clone(),
+        clear: args.clear.clone(),
+    }
+}
+
+// Map the ISBN arguments of a command
+//
+fn to_isbns(list: &[String]) -> Vec<Isbn> {
+    list.iter().map(|isbn| Isbn::new(isbn)).collect()
+}
+
+// Count the input ISBN which could not be fetched
+//
+fn failed(stats: &Stats) -> usize {
+    stats
+        .outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome.status, OutcomeStatus::Failed(_)))
+        .count()
 }
 
 // Available application errors
 //
+// Every error maps to a process exit code.
+//
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Configuration error: {0}")]
     ConfigError(String),
     #[error("Input data error. {0}")]
     InputError(String),
-    #[error("Runtime error: {0}")]
-    RuntimeError(String),
+    #[error("Storage error: {0}")]
+    StorageError(String),
+    #[error("Network error: {0}")]
+    NetworkError(String),
+    #[error("Authentication error: {0}")]
+    AuthError(String),
+    #[error("{0} of {1} books could not be resolved")]
+    PartialFailure(usize, usize),
+    #[error("None of the {0} books could be resolved")]
+    Unresolved(usize),
+}
+
+impl AppError {
+    /// Exit code for the error
+    ///
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::ConfigError(_) => exitcode::CONFIG,
+            AppError::InputError(_) => exitcode::NOINPUT,
+            AppError::StorageError(_) => exitcode::IOERR,
+            AppError::NetworkError(_) => exitcode::UNAVAILABLE,
+            AppError::AuthError(_) => exitcode::NOPERM,
+            AppError::PartialFailure(_, _) => PARTIAL,
+            AppError::Unresolved(_) => UNRESOLVED,
+        }
+    }
+
+    /// Error for a completed run with failed lookups.
+    ///
+    /// When some lookups succeeded the run is a partial failure.
+    /// When all of them failed, auth and network errors prevail
+    /// on the generic unresolved error.
+    ///
+    pub fn from_stats(stats: &Stats) -> Option<AppError> {
+        let errors: Vec<_> = stats
+            .outcomes
+            .iter()
+            .filter_map(|outcome| match &outcome.status {
+                OutcomeStatus::Failed(e) => Some(e),
+                _ => None,
+            })
+            .collect();
+        let attempted = stats.new_volumes + errors.len();
+
+        if errors.is_empty() {
+            return None;
+        }
+        if stats.new_volumes > 0 {
+            return Some(AppError::PartialFailure(errors.len(), attempted));
+        }
+        if let Some(e) = errors.iter().find(|e| e.is_auth()) {
+            return Some(AppError::AuthError(e.to_string()));
+        }
+        if errors.iter().all(|e| e.is_network()) {
+            return Some(AppError::NetworkError(errors[0].to_string()));
+        }
+        Some(AppError::Unresolved(errors.len()))
+    }
 }
 
 impl From<BuildError> for AppError {
@@ -79,8 +1206,84 @@ impl From<InputError> for AppError {
     }
 }
 
-impl From<&str> for AppError {
-    fn from(err: &str) -> AppError {
-        AppError::RuntimeError(err.to_string())
+impl From<LogError> for AppError {
+    fn from(err: LogError) -> AppError {
+        AppError::ConfigError(err.to_string())
+    }
+}
+
+impl From<CommandError> for AppError {
+    fn from(err: CommandError) -> AppError {
+        match err {
+            CommandError::UnreadableFolder(_, _) => AppError::InputError(err.to_string()),
+            CommandError::Fetch(e) => AppError::from(e),
+            CommandError::Storage(e) => AppError::from(e),
+            CommandError::Loan(e) => AppError::from(e),
+            CommandError::Reading(e) => AppError::from(e),
+            CommandError::Holding(e) => AppError::from(e),
+            CommandError::Override(e) => AppError::from(e),
+            CommandError::Work(e) => AppError::from(e),
+            CommandError::Wish(e) => AppError::from(e),
+        }
+    }
+}
+
+impl From<StorageError> for AppError {
+    fn from(err: StorageError) -> AppError {
+        AppError::StorageError(err.to_string())
+    }
+}
+
+impl From<FetchError> for AppError {
+    fn from(err: FetchError) -> AppError {
+        if err.is_auth() {
+            AppError::AuthError(err.to_string())
+        } else if err.is_network() {
+            AppError::NetworkError(err.to_string())
+        } else {
+            AppError::Unresolved(1)
+        }
+    }
+}
+
+impl From<LoanError> for AppError {
+    fn from(err: LoanError) -> AppError {
+        AppError::InputError(err.to_string())
+    }
+}
+
+impl From<ReadingError> for AppError {
+    fn from(err: ReadingError) -> AppError {
+        AppError::InputError(err.to_string())
+    }
+}
+
+impl From<HoldingError> for AppError {
+    fn from(err: HoldingError) -> AppError {
+        AppError::InputError(err.to_string())
+    }
+}
+
+impl From<OverrideError> for AppError {
+    fn from(err: OverrideError) -> AppError {
+        AppError::InputError(err.to_string())
+    }
+}
+
+impl From<WorkError> for AppError {
+    fn from(err: WorkError) -> AppError {
+        AppError::InputError(err.to_string())
+    }
+}
+
+impl From<WishError> for AppError {
+    fn from(err: WishError) -> AppError {
+        AppError::InputError(err.to_string())
+    }
+}
+
+impl From<HttpError> for AppError {
+    fn from(err: HttpError) -> AppError {
+        AppError::ConfigError(err.to_string())
     }
 }
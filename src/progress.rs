@@ -0,0 +1,211 @@
+use crate::books::{FetchEvent, FetchObserver};
+use crate::cli::Args;
+use crate::input::has_terminal_stderr;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Interval between two plain text progress lines
+///
+const PLAIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The bar on screen, hidden while other output is written
+///
+static ACTIVE_BAR: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Displays the fetch progress on stderr.
+///
+/// On a terminal it draws a progress bar, otherwise it
+/// falls back to plain text lines printed periodically.
+///
+pub struct Progress {
+    display: Display,
+    state: RefCell<State>,
+}
+
+enum Display {
+    Bar(ProgressBar),
+    Plain,
+}
+
+#[derive(Default)]
+struct State {
+    total: usize,
+    done: usize,
+    found: usize,
+    failed: usize,
+    started: Option<Instant>,
+    last_line: Option<Instant>,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Progress {
+    /// Returns the progress display unless disabled from CLI.
+    ///
+    pub fn from_cli_args(args: &Args) -> Option<Progress> {
+        if args.quiet || args.no_progress {
+            return None;
+        }
+
+        let display = match has_terminal_stderr() {
+            true => Display::Bar(create_bar()),
+            false => Display::Plain,
+        };
+
+        Some(Progress {
+            display,
+            state: RefCell::new(State::default()),
+        })
+    }
+
+    fn started(&self, total: usize) {
+        let mut state = self.state.borrow_mut();
+        state.total = total;
+        state.last_line = Some(Instant::now());
+
+        match &self.display {
+            Display::Bar(bar) => bar.set_length(total as u64),
+            Display::Plain => eprintln!("Fetching {} volumes...", total),
+        }
+    }
+
+    // The clock starts with the first lookup, so the time spent
+    // loading the library does not count in the ETA
+    //
+    fn lookup(&self, isbn: &str) {
+        let mut state = self.state.borrow_mut();
+        let first = state.started.is_none();
+        if first {
+            state.started = Some(Instant::now());
+        }
+
+        if let Display::Bar(bar) = &self.display {
+            if first {
+                bar.reset();
+                bar.set_draw_target(ProgressDrawTarget::stderr());
+                bar.enable_steady_tick(Duration::from_millis(200));
+                set_active(Some(bar.clone()));
+            }
+            bar.set_message(state.message(isbn));
+        }
+    }
+
+    fn completed(&self, isbn: &str, found: bool) {
+        let mut state = self.state.borrow_mut();
+        state.done += 1;
+        match found {
+            true => state.found += 1,
+            false => state.failed += 1,
+        };
+
+        match &self.display {
+            Display::Bar(bar) => {
+                bar.set_message(state.message(isbn));
+                bar.inc(1);
+            }
+            Display::Plain => {
+                let due = state
+                    .last_line
+                    .is_none_or(|last| last.elapsed() >= PLAIN_INTERVAL);
+                if due && state.done < state.total {
+                    eprintln!("{}", state.line());
+                    state.last_line = Some(Instant::now());
+                }
+            }
+        }
+    }
+
+    fn finished(&self) {
+        let state = self.state.borrow();
+        match &self.display {
+            Display::Bar(bar) => {
+                set_active(None);
+                bar.finish_and_clear();
+            }
+            Display::Plain if state.total > 0 => eprintln!("{}", state.line()),
+            Display::Plain => (),
+        }
+    }
+}
+
+impl FetchObserver for Progress {
+    fn notify(&self, event: &FetchEvent) {
+        match event {
+            FetchEvent::Started { total } => self.started(*total),
+            FetchEvent::Lookup { isbn } => self.lookup(&isbn.to_string()),
+            FetchEvent::Completed { isbn, found } => {
+                self.completed(&isbn.to_string(), *found)
+            }
+            FetchEvent::Finished => self.finished(),
+        }
+    }
+}
+
+impl State {
+    /// Bar message: counters and current ISBN
+    ///
+    fn message(&self, isbn: &str) -> String {
+        format!("found {} failed {} | {}", self.found, self.failed, isbn)
+    }
+
+    /// Plain text progress line with the estimated time left
+    ///
+    fn line(&self) -> String {
+        let mut line = format!(
+            "Fetched {}/{} (found {}, failed {})",
+            self.done, self.total, self.found, self.failed
+        );
+
+        if let Some(eta) = self.eta() {
+            line.push_str(&format!(", ETA {}s", eta.as_secs()));
+        }
+        line
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        let started = self.started?;
+        if self.done == 0 || self.done >= self.total {
+            return None;
+        }
+        let per_item = started.elapsed() / self.done as u32;
+        Some(per_item * (self.total - self.done) as u32)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Run a function writing to stderr with the progress bar
+/// hidden, so the output does not tear the bar.
+///
+pub fn suspend<F: FnOnce() -> R, R>(f: F) -> R {
+    let bar = ACTIVE_BAR.lock().ok().and_then(|active| active.clone());
+    match bar {
+        Some(bar) => bar.suspend(f),
+        None => f(),
+    }
+}
+
+fn set_active(bar: Option<ProgressBar>) {
+    if let Ok(mut active) = ACTIVE_BAR.lock() {
+        *active = bar;
+    }
+}
+
+// Create the terminal progress bar, hidden until the first lookup
+//
+fn create_bar() -> ProgressBar {
+    let style = ProgressStyle::with_template(
+        "{spinner} [{elapsed_precise}] {wide_bar} {pos}/{len} ETA {eta} {msg}",
+    )
+    .unwrap_or_else(|_| ProgressStyle::default_bar());
+
+    let bar = ProgressBar::with_draw_target(Some(0), ProgressDrawTarget::hidden());
+    bar.set_style(style);
+    bar
+}
@@ -0,0 +1,242 @@
+use crate::app::Stats;
+use crate::books::{Isbn, OutcomeStatus};
+use crate::cli::{Args, ReportFormat};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{stdout, Result as IoResult, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Machine readable summary of a run
+///
+/// - started_at: run start as unix timestamp (seconds)
+/// - duration_ms: whole run duration
+/// - error: the error which stopped the run, if any
+/// - input: the ISBN list provided
+/// - skipped: ISBNs already present in the library
+/// - fetched: ISBNs fetched and appended to the library
+/// - low_confidence: ISBNs appended from a search result not
+///   matching them, to be checked by hand
+/// - failed: ISBNs which could not be fetched, with the reason
+/// - library_size: volumes in the library after the run
+/// - requests: HTTP requests sent to the API
+/// - requests_saved: volume requests avoided using the search results
+///
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub input: Vec<String>,
+    pub skipped: Vec<String>,
+    pub fetched: Vec<Fetched>,
+    pub low_confidence: Vec<Fetched>,
+    pub failed: Vec<Failed>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_saved: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Fetched {
+    pub isbn: String,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Failed {
+    pub isbn: String,
+    pub reason: String,
+    pub elapsed_ms: u64,
+}
+
+/// Writes the report in the requested format, either to
+/// a file or to stdout.
+///
+pub struct ReportWriter {
+    format: ReportFormat,
+    file: Option<String>,
+}
+
+/// A JSON Lines record: one line per ISBN and a final summary
+///
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Line<'a> {
+    Isbn {
+        isbn: &'a str,
+        status: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<&'a str>,
+        elapsed_ms: u64,
+    },
+    Summary {
+        started_at: u64,
+        duration_ms: u64,
+        success: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+        input: usize,
+        skipped: usize,
+        fetched: usize,
+        low_confidence: usize,
+        failed: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        library_size: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        requests: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        requests_saved: Option<usize>,
+    },
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl Report {
+    /// Build the report from the run result.
+    ///
+    /// The run failed when there is an error, even if some books
+    /// were fetched. The input is taken from the stats when the
+    /// run got that far.
+    ///
+    pub fn new<E: ToString>(
+        started: SystemTime,
+        elapsed: Duration,
+        input: &[Isbn],
+        stats: Option<&Stats>,
+        error: Option<&E>,
+    ) -> Report {
+        let mut report = Report {
+            started_at: started
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            duration_ms: elapsed.as_millis() as u64,
+            success: error.is_none(),
+            error: error.map(|e| e.to_string()),
+            input: input.iter().map(|isbn| isbn.to_string()).collect(),
+            ..Report::default()
+        };
+
+        if let Some(stats) = stats {
+            report.library_size = Some(stats.library_size);
+            report.requests = Some(stats.requests);
+            report.requests_saved = Some(stats.requests_saved);
+            report.input = stats.input.iter().map(|isbn| isbn.to_string()).collect();
+            for outcome in stats.outcomes.iter() {
+                let isbn = outcome.isbn.to_string();
+                let elapsed_ms = outcome.elapsed.as_millis() as u64;
+                match &outcome.status {
+                    OutcomeStatus::Skipped => report.skipped.push(isbn),
+                    OutcomeStatus::Fetched
+                        if stats.low_confidence.contains(&outcome.isbn) =>
+                    {
+                        report.low_confidence.push(Fetched { isbn, elapsed_ms })
+                    }
+                    OutcomeStatus::Fetched => {
+                        report.fetched.push(Fetched { isbn, elapsed_ms })
+                    }
+                    OutcomeStatus::Failed(e) => report.failed.push(Failed {
+                        isbn,
+                        reason: e.to_string(),
+                        elapsed_ms,
+                    }),
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Serialize the report as JSON Lines.
+    ///
+    fn to_lines(&self) -> serde_json::Result<String> {
+        let mut lines = vec![];
+
+        for isbn in self.skipped.iter() {
+            lines.push(serde_json::to_string(&Line::Isbn {
+                isbn,
+                status: "skipped",
+                reason: None,
+                elapsed_ms: 0,
+            })?);
+        }
+        for item in self.fetched.iter() {
+            lines.push(serde_json::to_string(&Line::Isbn {
+                isbn: &item.isbn,
+                status: "fetched",
+                reason: None,
+                elapsed_ms: item.elapsed_ms,
+            })?);
+        }
+        for item in self.low_confidence.iter() {
+            lines.push(serde_json::to_string(&Line::Isbn {
+                isbn: &item.isbn,
+                status: "low_confidence",
+                reason: None,
+                elapsed_ms: item.elapsed_ms,
+            })?);
+        }
+        for item in self.failed.iter() {
+            lines.push(serde_json::to_string(&Line::Isbn {
+                isbn: &item.isbn,
+                status: "failed",
+                reason: Some(&item.reason),
+                elapsed_ms: item.elapsed_ms,
+            })?);
+        }
+
+        lines.push(serde_json::to_string(&Line::Summary {
+            started_at: self.started_at,
+            duration_ms: self.duration_ms,
+            success: self.success,
+            error: self.error.as_deref(),
+            input: self.input.len(),
+            skipped: self.skipped.len(),
+            fetched: self.fetched.len(),
+            low_confidence: self.low_confidence.len(),
+            failed: self.failed.len(),
+            library_size: self.library_size,
+            requests: self.requests,
+            requests_saved: self.requests_saved,
+        })?);
+
+        Ok(lines.join("\n"))
+    }
+}
+
+impl ReportWriter {
+    /// Returns the writer if a report was requested.
+    ///
+    pub fn from_cli_args(args: &Args) -> Option<ReportWriter> {
+        args.report.clone().map(|format| ReportWriter {
+            format,
+            file: args.report_file.clone(),
+        })
+    }
+
+    /// True when the report takes the place of the
+    /// human readable summary on stdout.
+    ///
+    pub fn uses_stdout(&self) -> bool {
+        self.file.is_none()
+    }
+
+    pub fn write(&self, report: &Report) -> IoResult<()> {
+        let data = match self.format {
+            ReportFormat::Json => serde_json::to_string_pretty(report)?,
+            ReportFormat::Jsonl => report.to_lines()?,
+        };
+
+        match &self.file {
+            Some(path) => File::create(path).and_then(|mut f| writeln!(f, "{}", data)),
+            None => writeln!(stdout(), "{}", data),
+        }
+    }
+}
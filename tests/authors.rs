@@ -0,0 +1,80 @@
+mod common;
+
+use common::start;
+
+const ALGORITHMS: &str = "9780262033848";
+const ROSA: &str = "9788845292613";
+const MISMATCH: &str = "9781491927281";
+
+#[test]
+fn lists_authors_surname_first_with_their_books() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS, ROSA]);
+
+    let output = sandbox.stdout(&["authors"]);
+
+    let names: Vec<&str> = output.lines().filter(|l| !l.starts_with(' ')).collect();
+    assert_eq!(
+        names,
+        vec![
+            "Cormen, Thomas H. (1)",
+            "Eco, Umberto (1)",
+            "Leiserson, Charles E. (1)",
+            "Rivest, Ronald L. (1)",
+            "Stein, Clifford (1)",
+        ]
+    );
+    assert!(output.contains(&format!("    {}  Il nome della rosa", ROSA)));
+}
+
+#[test]
+fn groups_the_spellings_of_an_author() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA, MISMATCH]);
+    sandbox.run(&["edit", MISMATCH, "--set", "authors=ECO, Umbérto"]);
+
+    let output = sandbox.stdout(&["authors"]);
+
+    assert_eq!(output.lines().next(), Some("Eco, Umberto (2)"));
+    assert_eq!(output.lines().count(), 3);
+}
+
+#[test]
+fn keeps_name_suffixes_apart_from_the_surname() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA, MISMATCH]);
+    sandbox.run(&["edit", ROSA, "--set", "authors=Martin Luther King Jr."]);
+    sandbox.run(&["edit", MISMATCH, "--set", "authors=King, Martin Luther, Jr"]);
+
+    let output = sandbox.stdout(&["authors"]);
+
+    assert_eq!(output.lines().next(), Some("King, Martin Luther, Jr. (2)"));
+}
+
+#[test]
+fn folds_accents_of_any_latin_letter() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA, MISMATCH]);
+    sandbox.run(&["edit", ROSA, "--set", "authors=Orhan Pamuğ Ķőņ"]);
+    sandbox.run(&["edit", MISMATCH, "--set", "authors=Orhan Pamug Kon"]);
+
+    let output = sandbox.stdout(&["authors"]);
+
+    assert_eq!(output.lines().filter(|l| !l.starts_with(' ')).count(), 1);
+}
+
+#[test]
+fn aliases_merge_authors() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA, MISMATCH]);
+
+    let output = sandbox.run(&["authors", "alias", "Bloch, Joshua", "Umberto Eco"]);
+    let index = sandbox.stdout(&["authors"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        sandbox.library()["aliases"],
+        serde_json::json!({ "Bloch, Joshua": "Umberto Eco" })
+    );
+    assert_eq!(index.lines().next(), Some("Eco, Umberto (2)"));
+}
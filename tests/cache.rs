@@ -0,0 +1,129 @@
+mod common;
+
+use common::{start, titles, Sandbox};
+use serde_json::json;
+use std::fs::remove_file;
+
+const FOUND: &str = "9780262033848";
+const FOUND_PARTIAL: &str = "9788845292613";
+
+// Enable the cache in the sandbox, keeping the responses `ttl` seconds
+//
+fn enable_cache(sandbox: &Sandbox, ttl: u64) {
+    let dir = sandbox.path("cache");
+    sandbox.configure(json!({
+        "cache": { "enabled": true, "dir": dir, "ttl": ttl }
+    }));
+}
+
+// Forget the fetched books, so the next run looks them up again
+//
+fn forget_library(sandbox: &Sandbox) {
+    remove_file(sandbox.path("library.json")).unwrap();
+}
+
+#[test]
+fn serves_a_second_run_from_the_cache() {
+    let (server, sandbox) = start();
+    enable_cache(&sandbox, 3600);
+
+    sandbox.run(&[FOUND]);
+    let sent = server.requests().len();
+    forget_library(&sandbox);
+    let output = sandbox.run(&[FOUND]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(sent > 0);
+    assert_eq!(server.requests().len(), sent);
+    assert_eq!(
+        titles(&sandbox.library())[FOUND],
+        "Introduction to Algorithms"
+    );
+}
+
+#[test]
+fn revalidates_an_expired_response_with_its_etag() {
+    let (server, sandbox) = start();
+    enable_cache(&sandbox, 0);
+
+    sandbox.run(&[FOUND]);
+    forget_library(&sandbox);
+    let output = sandbox.run(&[FOUND]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let received = server.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].headers.get("if-none-match"), None);
+    assert!(received[1].headers["if-none-match"].starts_with('"'));
+    assert_eq!(
+        titles(&sandbox.library())[FOUND],
+        "Introduction to Algorithms"
+    );
+}
+
+#[test]
+fn serves_cached_responses_offline() {
+    let (server, sandbox) = start();
+    enable_cache(&sandbox, 0);
+
+    sandbox.run(&[FOUND]);
+    let sent = server.requests().len();
+    forget_library(&sandbox);
+    let output = sandbox.run(&["--offline", FOUND]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(server.requests().len(), sent);
+    assert_eq!(
+        titles(&sandbox.library())[FOUND],
+        "Introduction to Algorithms"
+    );
+}
+
+#[test]
+fn leaves_uncached_books_unresolved_offline() {
+    let (server, sandbox) = start();
+    enable_cache(&sandbox, 3600);
+
+    let output = sandbox.run(&["--offline", FOUND]);
+
+    assert_ne!(output.status.code(), Some(0));
+    assert!(server.requests().is_empty());
+    assert!(titles(&sandbox.library()).is_empty());
+}
+
+#[test]
+fn shows_and_prunes_the_cached_responses() {
+    let (server, sandbox) = start();
+    enable_cache(&sandbox, 3600);
+    sandbox.run(&[FOUND, FOUND_PARTIAL]);
+    let sent = server.requests().len();
+
+    let stats = sandbox.stdout(&["cache", "stats"]);
+    assert!(stats.contains(&format!("Cached responses: {}", sent)));
+    assert!(stats.contains("Expired: 0"));
+    let pruned = sandbox.stdout(&["cache", "prune"]);
+    assert!(pruned.contains("Removed expired responses: 0"));
+
+    enable_cache(&sandbox, 0);
+    let stats = sandbox.stdout(&["cache", "stats"]);
+    assert!(stats.contains(&format!("Expired: {}", sent)));
+    let pruned = sandbox.stdout(&["cache", "prune"]);
+    assert!(pruned.contains(&format!("Removed expired responses: {}", sent)));
+    assert!(sandbox
+        .stdout(&["cache", "stats"])
+        .contains("Cached responses: 0"));
+}
+
+#[test]
+fn clears_the_cache() {
+    let (server, sandbox) = start();
+    enable_cache(&sandbox, 3600);
+    sandbox.run(&[FOUND]);
+    forget_library(&sandbox);
+
+    let cleared = sandbox.stdout(&["cache", "clear"]);
+    sandbox.run(&[FOUND]);
+
+    assert!(cleared.contains("Removed responses: 1"));
+    assert_eq!(server.requests().len(), 2);
+}
@@ -0,0 +1,356 @@
+//! Test harness: a mock Google Books API replaying the recorded
+//! fixtures, and a sandbox running the binary against it.
+//!
+//! Fixtures are the files written by `books --record <dir>`, matched
+//! by their request url. Requests without a fixture get a 404.
+//! Responses carry an ETag, and a request sending it back in
+//! `If-None-Match` gets a 304 without body.
+
+#![allow(dead_code)]
+
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all, write};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Mock API server replaying the fixtures of a folder
+///
+pub struct MockServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+/// A request received by the mock server, with its url relative
+/// to the base url and its headers, names in lowercase
+///
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub url: String,
+    pub headers: HashMap<String, String>,
+}
+
+/// A temporary folder holding the config, the library
+/// and the reports of a test run
+///
+pub struct Sandbox {
+    dir: PathBuf,
+    base_url: String,
+}
+
+struct Fixture {
+    status: u16,
+    body: String,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Type implementation
+/////////////////////////////////////////////////////////////////////////////
+
+impl MockServer {
+    /// Start the server on a free local port, serving
+    /// the fixtures found in `tests/fixtures`.
+    ///
+    pub fn start() -> MockServer {
+        let fixtures = Arc::new(load_fixtures(&fixtures_dir()));
+        let requests = Arc::new(Mutex::new(vec![]));
+        let listener = TcpListener::bind("127.0.0.1:0").expect("cannot bind mock server");
+        let base_url = format!("http://{}/books/v1/", listener.local_addr().unwrap());
+
+        let log = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                serve(stream, &fixtures, &log);
+            }
+        });
+
+        MockServer { base_url, requests }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Urls requested so far, relative to the base url
+    ///
+    pub fn requests(&self) -> Vec<String> {
+        self.received()
+            .into_iter()
+            .map(|request| request.url)
+            .collect()
+    }
+
+    /// Requests received so far, with their headers
+    ///
+    pub fn received(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Sandbox {
+    /// Create an empty sandbox with a config pointing to the server.
+    /// The response cache is disabled, so every test hits the server.
+    ///
+    pub fn new(server: &MockServer) -> Sandbox {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "books-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+
+        let sandbox = Sandbox {
+            dir,
+            base_url: server.base_url().to_string(),
+        };
+        sandbox.configure(serde_json::json!({}));
+        sandbox
+    }
+
+    /// Write the config, merging the given settings
+    /// over the sandbox defaults.
+    ///
+    pub fn configure(&self, settings: Value) {
+        let mut config = serde_json::json!({
+            "base_url": self.base_url,
+            "api_key": "",
+            "output": "library.json",
+            "cache": { "enabled": false },
+            "http": { "timeout": 5 }
+        });
+        if let (Some(config), Some(settings)) =
+            (config.as_object_mut(), settings.as_object())
+        {
+            for (key, value) in settings {
+                config.insert(key.clone(), value.clone());
+            }
+        }
+        write(self.path("config.json"), config.to_string()).unwrap();
+    }
+
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    /// Run the binary with the given arguments, the sandbox
+    /// config and library, and an empty stdin.
+    ///
+    pub fn run(&self, args: &[&str]) -> Output {
+        self.command(args)
+            .stdin(Stdio::null())
+            .output()
+            .expect("cannot run books")
+    }
+
+    /// Run the binary like `run`, writing `input` to its stdin
+    ///
+    pub fn run_with_input(&self, args: &[&str], input: &str) -> Output {
+        let mut child = self
+            .command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("cannot run books");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().expect("cannot run books")
+    }
+
+    /// The command running the binary in the sandbox,
+    /// for tests driving the process themselves
+    ///
+    pub fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_books"));
+        command
+            .current_dir(&self.dir)
+            .env_remove("BOOK_OUTPUT")
+            .env_remove("BOOK_CONFIG")
+            .arg("--config-file")
+            .arg(self.path("config.json"))
+            .arg("--output-file")
+            .arg(self.path("library.json"))
+            .arg("--no-progress")
+            .args(args);
+        command
+    }
+
+    /// Run the binary like `run` and return its stdout
+    ///
+    pub fn stdout(&self, args: &[&str]) -> String {
+        String::from_utf8_lossy(&self.run(args).stdout).into_owned()
+    }
+
+    /// The saved library, or Null when it was never written
+    ///
+    pub fn library(&self) -> Value {
+        self.json("library.json")
+    }
+
+    pub fn json(&self, name: &str) -> Value {
+        read_to_string(self.path(name))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or(Value::Null)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.dir);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Public API
+/////////////////////////////////////////////////////////////////////////////
+
+/// Start a mock server and an empty sandbox using it
+///
+pub fn start() -> (MockServer, Sandbox) {
+    let server = MockServer::start();
+    let sandbox = Sandbox::new(&server);
+    (server, sandbox)
+}
+
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Titles of the library volumes, by ISBN
+///
+pub fn titles(library: &Value) -> HashMap<String, String> {
+    library["volumes"]
+        .as_array()
+        .map(|volumes| {
+            volumes
+                .iter()
+                .map(|volume| {
+                    (
+                        volume["isbn"]["value"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        volume["title"].as_str().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn load_fixtures(dir: &Path) -> HashMap<String, Fixture> {
+    let mut fixtures = HashMap::new();
+
+    for entry in read_dir(dir).expect("cannot read fixtures") {
+        let path = entry.unwrap().path();
+        let data: Value = serde_json::from_str(&read_to_string(&path).unwrap())
+            .unwrap_or_else(|e| panic!("invalid fixture {}: {}", path.display(), e));
+
+        let body = match (&data["body"], &data["raw"]) {
+            (Value::Null, Value::String(raw)) => raw.clone(),
+            (body, _) => body.to_string(),
+        };
+        fixtures.insert(
+            data["url"].as_str().unwrap().to_string(),
+            Fixture {
+                status: data["status"].as_u64().unwrap() as u16,
+                body,
+            },
+        );
+    }
+
+    fixtures
+}
+
+// Answers a single request, closing the connection after it
+//
+fn serve(
+    mut stream: TcpStream,
+    fixtures: &HashMap<String, Fixture>,
+    log: &Mutex<Vec<Request>>,
+) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut headers = HashMap::new();
+    loop {
+        let mut header = String::new();
+        match reader.read_line(&mut header) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if header.trim().is_empty() => break,
+            Ok(_) => {
+                if let Some((name, value)) = header.split_once(':') {
+                    headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+                }
+            }
+        }
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let url = without_key(target.trim_start_matches("/books/v1/"));
+    let if_none_match = headers.get("if-none-match").cloned();
+    log.lock().unwrap().push(Request {
+        url: url.clone(),
+        headers,
+    });
+
+    let (status, body) = match fixtures.get(&url) {
+        Some(fixture) => (fixture.status, fixture.body.as_str()),
+        None => (404, r#"{"error":{"code":404,"message":"No fixture"}}"#),
+    };
+    let etag = etag(body);
+    let (status, body) = match if_none_match {
+        Some(tag) if status == 200 && tag == etag => (304, ""),
+        _ => (status, body),
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        etag,
+        body.len(),
+        body
+    );
+}
+
+// The ETag sent with a response body
+//
+fn etag(body: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+// Drops the API key from the query, as the recorder does
+//
+fn without_key(url: &str) -> String {
+    match url.split_once('?') {
+        Some((path, query)) => {
+            let pairs: Vec<&str> = query
+                .split('&')
+                .filter(|pair| !pair.starts_with("key="))
+                .collect();
+            match pairs.is_empty() {
+                true => path.to_string(),
+                false => format!("{}?{}", path, pairs.join("&")),
+            }
+        }
+        None => url.to_string(),
+    }
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::{start, titles};
+use serde_json::json;
+
+const ALGORITHMS: &str = "9780262033848";
+const ROSA: &str = "9788845292613";
+
+#[test]
+fn adds_copies_without_fetching_known_books_again() {
+    let (server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS]);
+    let fetched = server.requests().len();
+
+    let first = sandbox.run(&["copy", "add", ALGORITHMS, "--location", "Floor 1"]);
+    let second = sandbox.run(&[
+        "copy",
+        "add",
+        ALGORITHMS,
+        "--id",
+        "ACC-0042",
+        "--location",
+        "Floor 3",
+        "--condition",
+        "worn",
+        "--acquired",
+        "2021-05-04",
+        "--price",
+        "89.00 EUR",
+    ]);
+
+    assert_eq!(first.status.code(), Some(0));
+    assert_eq!(second.status.code(), Some(0));
+    assert_eq!(server.requests().len(), fetched);
+    let library = sandbox.library();
+    assert_eq!(library["volumes"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        library["holdings"][ALGORITHMS],
+        json!([
+            { "id": format!("{}-1", ALGORITHMS), "location": "Floor 1" },
+            {
+                "id": "ACC-0042",
+                "location": "Floor 3",
+                "condition": "worn",
+                "acquired": "2021-05-04",
+                "price": "89.00 EUR"
+            }
+        ])
+    );
+}
+
+#[test]
+fn fetches_new_books_when_adding_a_copy() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&["copy", "add", ROSA, "--id", "B-1"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let library = sandbox.library();
+    assert_eq!(library["volumes"][0]["title"], "Il nome della rosa");
+    assert_eq!(library["holdings"][ROSA], json!([{ "id": "B-1" }]));
+}
+
+#[test]
+fn rejects_duplicate_copy_ids() {
+    let (_server, sandbox) = start();
+    sandbox.run(&["copy", "add", ROSA, "--id", "B-1"]);
+
+    let output = sandbox.run(&["copy", "add", ALGORITHMS, "--id", "B-1"]);
+
+    assert_eq!(output.status.code(), Some(66));
+    assert!(sandbox.library()["holdings"].get(ALGORITHMS).is_none());
+}
+
+#[test]
+fn lends_copies_separately() {
+    let (_server, sandbox) = start();
+    sandbox.run(&["copy", "add", ROSA, "--id", "B-1"]);
+    sandbox.run(&["copy", "add", ROSA, "--id", "B-2"]);
+
+    let first = sandbox.run(&["lend", ROSA, "--to", "Anna", "--copy", "B-1"]);
+    let second = sandbox.run(&["lend", ROSA, "--to", "Marco", "--copy", "B-2"]);
+    let unknown = sandbox.run(&["lend", ROSA, "--to", "Luca", "--copy", "B-3"]);
+
+    assert_eq!(first.status.code(), Some(0));
+    assert_eq!(second.status.code(), Some(0));
+    assert_eq!(unknown.status.code(), Some(66));
+    let lent = sandbox.stdout(&["loans"]);
+    assert_eq!(lent.lines().count(), 2);
+}
+
+#[test]
+fn lends_a_book_with_copies_one_copy_at_a_time() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS]);
+    sandbox.run(&["copy", "add", ROSA, "--id", "B-1"]);
+    sandbox.run(&["copy", "add", ROSA, "--id", "B-2"]);
+
+    let whole = sandbox.run(&["lend", ALGORITHMS, "--to", "Anna"]);
+    let copy = sandbox.run(&["copy", "add", ALGORITHMS, "--id", "A-1"]);
+    let again = sandbox.run(&["lend", ALGORITHMS, "--copy", "A-1", "--to", "Marco"]);
+    let any = sandbox.run(&["lend", ROSA, "--to", "Anna"]);
+    let taken = sandbox.run(&["lend", ROSA, "--copy", "B-1", "--to", "Marco"]);
+    let other = sandbox.run(&["lend", ROSA, "--to", "Luca"]);
+    let none_left = sandbox.run(&["lend", ROSA, "--to", "Sara"]);
+    let ambiguous = sandbox.run(&["return", ROSA]);
+    let returned = sandbox.run(&["return", ROSA, "--copy", "B-2"]);
+
+    assert_eq!(whole.status.code(), Some(0));
+    assert_eq!(copy.status.code(), Some(0));
+    assert_eq!(again.status.code(), Some(66));
+    assert_eq!(any.status.code(), Some(0));
+    assert_eq!(taken.status.code(), Some(66));
+    assert_eq!(other.status.code(), Some(0));
+    assert_eq!(none_left.status.code(), Some(66));
+    assert_eq!(ambiguous.status.code(), Some(66));
+    assert_eq!(returned.status.code(), Some(0));
+    let loans = &sandbox.library()["loans"];
+    assert_eq!(loans[ALGORITHMS].as_array().unwrap().len(), 1);
+    assert_eq!(loans[ROSA][0]["copy"], "B-1");
+    assert_eq!(loans[ROSA][1]["copy"], "B-2");
+    assert!(loans[ROSA][0].get("returned").is_none());
+    assert!(loans[ROSA][1]["returned"].is_string());
+}
+
+#[test]
+fn keeps_copies_out_on_loan() {
+    let (_server, sandbox) = start();
+    sandbox.run(&["copy", "add", ROSA, "--id", "B-1"]);
+    sandbox.run(&["lend", ROSA, "--copy", "B-1", "--to", "Anna"]);
+
+    let lent = sandbox.run(&["copy", "remove", "B-1"]);
+    sandbox.run(&["return", ROSA]);
+    let back = sandbox.run(&["copy", "remove", "B-1"]);
+
+    assert_eq!(lent.status.code(), Some(66));
+    assert_eq!(back.status.code(), Some(0));
+    assert_eq!(titles(&sandbox.library()).len(), 1);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::start;
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+// Write an EPUB without identifier, with the given title and author
+//
+fn write_epub(path: &Path, title: &str, author: &str) {
+    let opf = format!(
+        r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="2.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>{}</dc:title>
+    <dc:creator>{}</dc:creator>
+  </metadata>
+</package>"#,
+        title, author
+    );
+
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    let options = SimpleFileOptions::default();
+    zip.start_file("META-INF/container.xml", options).unwrap();
+    zip.write_all(CONTAINER.as_bytes()).unwrap();
+    zip.start_file("OEBPS/content.opf", options).unwrap();
+    zip.write_all(opf.as_bytes()).unwrap();
+    zip.finish().unwrap();
+}
+
+#[test]
+fn keeps_apart_books_without_identifier_sharing_a_title() {
+    let (_server, sandbox) = start();
+    let dir = sandbox.path("ebooks");
+    create_dir_all(&dir).unwrap();
+    write_epub(&dir.join("a.epub"), "Poems", "Emily Dickinson");
+    write_epub(&dir.join("b.epub"), "Poems", "Walt Whitman");
+
+    let output = sandbox.run(&["scan-ebooks", dir.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let library = sandbox.library();
+    let volumes = library["volumes"].as_array().unwrap();
+    assert_eq!(volumes.len(), 2);
+    assert_ne!(volumes[0]["isbn"], volumes[1]["isbn"]);
+}
+
+#[test]
+fn fails_on_a_missing_folder() {
+    let (_server, sandbox) = start();
+    let dir = sandbox.path("missing");
+
+    let output = sandbox.run(&["scan-ebooks", dir.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(66));
+    assert!(!sandbox.path("library.json").exists());
+}
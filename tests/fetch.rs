@@ -0,0 +1,282 @@
+mod common;
+
+use common::{start, titles, Sandbox};
+use serde_json::json;
+use std::fs::write;
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const FOUND: &str = "9780262033848";
+const FOUND_ISBN10: &str = "0262033844";
+const FOUND_PARTIAL: &str = "9788845292613";
+const NOT_FOUND: &str = "9780306406157";
+const RATE_LIMITED: &str = "9780131103627";
+const SERVER_ERROR: &str = "9780596007126";
+const MALFORMED: &str = "9780201633610";
+const MISMATCH: &str = "9781491927281";
+const NO_DESCRIPTION: &str = "9780140449136";
+
+#[test]
+fn fetches_and_saves_found_books() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&[FOUND, FOUND_PARTIAL]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let titles = titles(&sandbox.library());
+    assert_eq!(titles[FOUND], "Introduction to Algorithms");
+    assert_eq!(titles[FOUND_PARTIAL], "Il nome della rosa");
+}
+
+#[test]
+fn builds_complete_volumes_from_the_search_result() {
+    let (server, sandbox) = start();
+
+    sandbox.run(&[FOUND]);
+
+    assert_eq!(server.requests().len(), 1);
+    let volume = &sandbox.library()["volumes"][0];
+    assert_eq!(volume["publisher"], "MIT Press");
+    assert_eq!(volume["pages"], 1313);
+    assert_eq!(volume["authors"].as_array().unwrap().len(), 4);
+}
+
+#[test]
+fn fills_incomplete_search_results_from_the_volume() {
+    let (server, sandbox) = start();
+
+    sandbox.run(&[FOUND_PARTIAL]);
+
+    assert_eq!(
+        server.requests().last().map(String::as_str),
+        Some("volumes/xMBrDwAAQBAJ")
+    );
+    let volume = &sandbox.library()["volumes"][0];
+    assert_eq!(volume["publisher"], "Bompiani");
+    assert_eq!(volume["language"], "it");
+    assert!(volume.get("low_confidence").is_none());
+}
+
+#[test]
+fn keeps_search_results_lacking_only_volume_extras() {
+    let (server, sandbox) = start();
+
+    sandbox.run(&[NO_DESCRIPTION]);
+
+    assert_eq!(server.requests().len(), 1);
+    let volume = &sandbox.library()["volumes"][0];
+    assert_eq!(volume["publisher"], "Penguin Classics");
+    assert_eq!(volume["description"], "");
+}
+
+#[test]
+fn sends_the_search_restrictions_with_the_query() {
+    let (server, sandbox) = start();
+
+    sandbox.run(&[
+        "--country",
+        "IT",
+        "--lang-restrict",
+        "it",
+        "--print-type",
+        "books",
+        FOUND,
+    ]);
+
+    assert_eq!(
+        server.requests()[0],
+        format!(
+            "volumes?projection=full&q=isbn%3A{}&langRestrict=it&printType=books&country=IT",
+            FOUND
+        )
+    );
+}
+
+#[test]
+fn skips_books_already_in_the_library() {
+    let (server, sandbox) = start();
+
+    sandbox.run(&[FOUND]);
+    let output = sandbox.run(&[FOUND, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(server.requests().len(), 1);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["skipped"], json!([FOUND]));
+    assert_eq!(report["library_size"], 1);
+}
+
+#[test]
+fn fetches_a_repeated_isbn_once() {
+    let (server, sandbox) = start();
+
+    let output = sandbox.run(&[FOUND, FOUND, FOUND_ISBN10, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(titles(&sandbox.library()).len(), 1);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["skipped"], json!([FOUND, FOUND_ISBN10]));
+}
+
+#[test]
+fn skips_the_isbn10_of_a_book_in_the_library() {
+    let (server, sandbox) = start();
+
+    sandbox.run(&[FOUND]);
+    let output = sandbox.run(&[FOUND_ISBN10]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(titles(&sandbox.library()).len(), 1);
+}
+
+#[test]
+fn reports_books_not_found() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&[NOT_FOUND, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["failed"][0]["isbn"], NOT_FOUND);
+    assert_eq!(report["failed"][0]["reason"], "No volume found");
+    assert_eq!(report["success"], false);
+    assert_eq!(report["error"], "None of the 1 books could be resolved");
+    assert_eq!(report["input"], json!([NOT_FOUND]));
+}
+
+#[test]
+fn exits_with_partial_failure_when_some_books_are_missing() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&[FOUND, NOT_FOUND]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(titles(&sandbox.library()).len(), 1);
+}
+
+#[test]
+fn rate_limits_are_network_errors() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&[RATE_LIMITED, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(69));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["failed"][0]["reason"], "Unexpected HTTP status 429");
+}
+
+#[test]
+fn server_errors_are_network_errors() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&[SERVER_ERROR]);
+
+    assert_eq!(output.status.code(), Some(69));
+    assert!(titles(&sandbox.library()).is_empty());
+}
+
+#[test]
+fn gives_up_on_servers_sending_no_data() {
+    let (_server, sandbox) = start();
+    let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/books/v1/", silent.local_addr().unwrap());
+    thread::spawn(move || {
+        let _open: Vec<_> = silent.incoming().collect();
+    });
+    sandbox.configure(json!({
+        "base_url": base_url,
+        "http": { "read_timeout": 1, "timeout": 60 }
+    }));
+
+    let started = Instant::now();
+    let output = sandbox.run(&[FOUND, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(69));
+    assert!(started.elapsed() < Duration::from_secs(30));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        report["failed"][0]["reason"],
+        "Request failed: no data received within 1s"
+    );
+}
+
+#[test]
+fn malformed_responses_leave_the_book_unresolved() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&[MALFORMED, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reason = report["failed"][0]["reason"].as_str().unwrap();
+    assert!(reason.starts_with("Invalid response"), "{}", reason);
+}
+
+#[test]
+fn flags_results_not_matching_the_isbn() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&[MISMATCH]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let volume = &sandbox.library()["volumes"][0];
+    assert_eq!(volume["isbn"]["value"], MISMATCH);
+    assert_eq!(volume["low_confidence"], true);
+}
+
+#[test]
+fn reports_low_confidence_matches_apart() {
+    let (server, sandbox) = start();
+
+    let output = sandbox.run(&[FOUND, MISMATCH, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["fetched"].as_array().unwrap().len(), 1);
+    assert_eq!(report["fetched"][0]["isbn"], FOUND);
+    assert_eq!(report["low_confidence"][0]["isbn"], MISMATCH);
+
+    let output = Sandbox::new(&server).run(&[MISMATCH]);
+    let summary = String::from_utf8_lossy(&output.stdout);
+    assert!(summary.contains("Fetched Volumes: 0"), "{}", summary);
+    assert!(summary.contains("Low confidence matches: 1"), "{}", summary);
+}
+
+#[test]
+fn refresh_keeps_the_volume_when_the_result_does_not_match() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[MISMATCH]);
+    let mut library = sandbox.library();
+    library["volumes"][0]["title"] = json!("Fluent Python");
+    library["volumes"][0]["low_confidence"] = json!(false);
+    write(sandbox.path("library.json"), library.to_string()).unwrap();
+
+    let output = sandbox.run(&["refresh", MISMATCH]);
+
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!(
+        "Warning: {} not refreshed: No search result matches the ISBN",
+        MISMATCH
+    )));
+    let volume = &sandbox.library()["volumes"][0];
+    assert_eq!(volume["title"], "Fluent Python");
+}
+
+#[test]
+fn rejects_results_not_matching_the_isbn_when_configured() {
+    let (_server, sandbox) = start();
+    sandbox.configure(json!({ "on_isbn_mismatch": "reject" }));
+
+    let output = sandbox.run(&[MISMATCH, "--report", "json"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        report["failed"][0]["reason"],
+        "No search result matches the ISBN"
+    );
+}
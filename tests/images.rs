@@ -0,0 +1,50 @@
+mod common;
+
+use common::{start, titles};
+use std::path::Path;
+
+const ALGORITHMS: &str = "9780262033848";
+
+fn image(name: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/images")
+        .join(name)
+        .display()
+        .to_string()
+}
+
+#[test]
+fn fetches_the_books_decoded_from_barcodes() {
+    let (_server, sandbox) = start();
+    let barcode = image("ean13-9780262033848.png");
+    let blank = image("blank.png");
+
+    let output = sandbox.run(&["--from-images", &barcode, &blank]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let report = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        report.contains(&format!("{}: {}", barcode, ALGORITHMS)),
+        "{}",
+        report
+    );
+    assert!(
+        report.contains(&format!("{}: no ISBN barcode found", blank)),
+        "{}",
+        report
+    );
+    assert_eq!(titles(&sandbox.library()).len(), 1);
+    assert!(titles(&sandbox.library()).contains_key(ALGORITHMS));
+}
+
+#[test]
+fn reports_images_without_barcodes() {
+    let (server, sandbox) = start();
+    let blank = image("blank.png");
+
+    let output = sandbox.run(&["--from-images", &blank]);
+
+    let report = String::from_utf8_lossy(&output.stderr);
+    assert!(report.contains("no ISBN barcode found"), "{}", report);
+    assert!(server.requests().is_empty());
+}
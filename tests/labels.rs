@@ -0,0 +1,96 @@
+mod common;
+
+use common::start;
+use std::fs::write;
+
+const ALGORITHMS: &str = "9780262033848";
+const ROSA: &str = "9788845292613";
+
+#[test]
+fn tags_and_shelves_fetched_books() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&["--tag", "To-Read", "--shelf", "office", ALGORITHMS]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let library = sandbox.library();
+    assert_eq!(library["tags"][ALGORITHMS], serde_json::json!(["to-read"]));
+    assert_eq!(
+        library["shelves"]["office"],
+        serde_json::json!([ALGORITHMS])
+    );
+}
+
+#[test]
+fn reads_labels_from_input_annotations() {
+    let (_server, sandbox) = start();
+    let input = format!("{} #cs #classic @office\n{} #novel\n", ALGORITHMS, ROSA);
+    write(sandbox.path("input.txt"), input).unwrap();
+
+    let output = sandbox.run(&["--input-file", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let library = sandbox.library();
+    assert_eq!(
+        library["tags"][ALGORITHMS],
+        serde_json::json!(["classic", "cs"])
+    );
+    assert_eq!(library["tags"][ROSA], serde_json::json!(["novel"]));
+    assert_eq!(
+        library["shelves"]["office"],
+        serde_json::json!([ALGORITHMS])
+    );
+}
+
+#[test]
+fn edits_tags_and_filters_the_list() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS, ROSA]);
+
+    let output = sandbox.run(&["tag", "add", "favourite", ALGORITHMS, ROSA]);
+    assert_eq!(output.status.code(), Some(0));
+    sandbox.run(&["tag", "remove", "favourite", ROSA]);
+
+    let listed = sandbox.stdout(&["list", "--tag", "favourite"]);
+    assert!(listed.contains(ALGORITHMS));
+    assert!(!listed.contains(ROSA));
+
+    let all = sandbox.stdout(&["list"]);
+    assert_eq!(all.lines().count(), 2);
+}
+
+#[test]
+fn counts_only_the_books_whose_labels_change() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS, ROSA]);
+    sandbox.run(&["tag", "add", "favourite", ALGORITHMS]);
+
+    let added = sandbox.stdout(&["tag", "add", "favourite", ALGORITHMS, ROSA]);
+    let removed = sandbox.stdout(&["shelf", "remove", "office", ALGORITHMS]);
+
+    assert!(added.contains("Updated books: 1"), "{}", added);
+    assert!(removed.contains("Updated books: 0"), "{}", removed);
+}
+
+#[test]
+fn searches_titles_and_authors_on_a_shelf() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS, ROSA]);
+    sandbox.run(&["shelf", "add", "living-room", ROSA]);
+
+    let found = sandbox.stdout(&["search", "eco"]);
+    assert!(found.contains("Il nome della rosa"));
+    assert_eq!(found.lines().count(), 1);
+
+    let shelved = sandbox.stdout(&["search", "cormen", "--shelf", "living-room"]);
+    assert!(shelved.is_empty());
+}
+
+#[test]
+fn fails_labelling_books_not_in_the_library() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&["tag", "add", "lost", ROSA]);
+
+    assert_eq!(output.status.code(), Some(4));
+}
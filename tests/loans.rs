@@ -0,0 +1,110 @@
+mod common;
+
+use common::start;
+use serde_json::json;
+use std::fs::{read_to_string, write};
+
+const ALGORITHMS: &str = "9780262033848";
+const ROSA: &str = "9788845292613";
+
+#[test]
+fn keeps_the_loan_history() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+
+    sandbox.run(&["lend", ROSA, "--to", "Anna", "--due", "2999-03-01"]);
+    let again = sandbox.run(&["lend", ROSA, "--to", "Marco"]);
+    sandbox.run(&["return", ROSA]);
+    sandbox.run(&["lend", ROSA, "--to", "Marco"]);
+
+    assert_eq!(again.status.code(), Some(66));
+    let history = sandbox.library()["loans"][ROSA].clone();
+    assert_eq!(history.as_array().unwrap().len(), 2);
+    assert_eq!(history[0]["borrower"], "Anna");
+    assert_eq!(history[0]["due"], "2999-03-01");
+    assert!(history[0]["returned"].is_string());
+    assert_eq!(history[1]["borrower"], "Marco");
+    assert!(history[1].get("returned").is_none());
+}
+
+#[test]
+fn reports_overdue_books() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS, ROSA]);
+    sandbox.run(&["lend", ALGORITHMS, "--to", "Anna", "--due", "2999-01-01"]);
+    sandbox.run(&["lend", ROSA, "--to", "Marco", "--due", "2999-01-01"]);
+    let mut library = sandbox.library();
+    library["loans"][ALGORITHMS][0]["lent"] = json!("1999-12-01");
+    library["loans"][ALGORITHMS][0]["due"] = json!("2000-01-01");
+    write(sandbox.path("library.json"), library.to_string()).unwrap();
+
+    let overdue = sandbox.stdout(&["overdue"]);
+    let lent = sandbox.stdout(&["loans"]);
+
+    assert_eq!(overdue.lines().count(), 1);
+    assert!(overdue.contains("lent to Anna"));
+    assert_eq!(lent.lines().count(), 2);
+}
+
+#[test]
+fn exports_due_dates_as_icalendar() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+    sandbox.run(&["lend", ROSA, "--to", "Anna, Marco", "--due", "2999-02-28"]);
+
+    let output = sandbox.run(&["loans", "--ics", "due.ics"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let ics = read_to_string(sandbox.path("due.ics")).unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.contains("DTSTART;VALUE=DATE:29990228\r\n"));
+    assert!(ics.contains("DTEND;VALUE=DATE:29990301\r\n"));
+    assert!(ics.contains("SUMMARY:Return \"Il nome della rosa\" (Anna\\, Marco)\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 75));
+}
+
+#[test]
+fn tells_apart_the_loans_of_the_same_day_in_icalendar() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+    sandbox.run(&["lend", ROSA, "--to", "Anna", "--due", "2999-03-01"]);
+    sandbox.run(&["loans", "--ics", "first.ics"]);
+    sandbox.run(&["return", ROSA]);
+    sandbox.run(&["lend", ROSA, "--to", "Anna", "--due", "2999-03-01"]);
+
+    sandbox.run(&["loans", "--ics", "second.ics"]);
+
+    let uid = |name: &str| {
+        read_to_string(sandbox.path(name))
+            .unwrap()
+            .lines()
+            .find(|line| line.starts_with("UID:"))
+            .map(str::to_string)
+            .unwrap()
+    };
+    assert_ne!(uid("first.ics"), uid("second.ics"));
+}
+
+#[test]
+fn rejects_due_dates_before_the_loan() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+
+    let output = sandbox.run(&["lend", ROSA, "--to", "Anna", "--due", "2000-01-01"]);
+
+    assert_eq!(output.status.code(), Some(66));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Due on 2000-01-01, before the loan on"));
+    assert!(sandbox.library().get("loans").is_none());
+}
+
+#[test]
+fn cannot_return_books_not_lent() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+
+    let output = sandbox.run(&["return", ROSA]);
+
+    assert_eq!(output.status.code(), Some(66));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not lent"));
+}
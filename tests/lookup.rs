@@ -0,0 +1,35 @@
+mod common;
+
+use common::start;
+
+#[test]
+fn searches_the_whole_title_and_author() {
+    let (server, sandbox) = start();
+
+    sandbox.run(&[
+        "lookup",
+        "--title",
+        "Il nome della rosa",
+        "--author",
+        "Umberto Eco",
+        "--accept-above",
+        "0.9",
+    ]);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].contains("intitle%3A%22Il+nome+della+rosa%22"));
+    assert!(requests[0].contains("inauthor%3A%22Umberto+Eco%22"));
+}
+
+#[test]
+fn rejects_scores_out_of_range() {
+    let (server, sandbox) = start();
+
+    let lookup = sandbox.run(&["lookup", "--title", "Dune", "--accept-above", "2"]);
+    let duplicates = sandbox.run(&["duplicates", "--min-score", "1.5"]);
+
+    assert_eq!(lookup.status.code(), Some(2));
+    assert_eq!(duplicates.status.code(), Some(2));
+    assert!(server.requests().is_empty());
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::start;
+use serde_json::json;
+
+const ROSA: &str = "9788845292613";
+
+#[test]
+fn overrides_survive_a_refresh() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+
+    let output = sandbox.run(&[
+        "edit",
+        ROSA,
+        "--set",
+        "title=The Name of the Rose",
+        "--set",
+        "authors=Umberto Eco; William Weaver",
+    ]);
+    sandbox.run(&["refresh", ROSA]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let library = sandbox.library();
+    assert_eq!(library["volumes"][0]["title"], "Il nome della rosa");
+    assert_eq!(
+        library["overrides"][ROSA],
+        json!({
+            "authors": "Umberto Eco; William Weaver",
+            "title": "The Name of the Rose"
+        })
+    );
+    let list = sandbox.stdout(&["list"]);
+    assert!(list.contains("The Name of the Rose - Umberto Eco, William Weaver"));
+}
+
+#[test]
+fn lists_the_overrides_in_effect() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+    sandbox.run(&["edit", ROSA, "--set", "title=The Name of the Rose"]);
+    sandbox.run(&["edit", ROSA, "--set", "pages=512"]);
+    sandbox.run(&["edit", ROSA, "--unset", "title"]);
+
+    let output = sandbox.stdout(&["overrides"]);
+
+    assert_eq!(output.lines().count(), 1);
+    assert!(output.starts_with(&format!("{}  pages = \"512\"", ROSA)));
+}
+
+#[test]
+fn rejects_unknown_fields_and_invalid_values() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+
+    let field = sandbox.run(&["edit", ROSA, "--set", "isbn=123"]);
+    let value = sandbox.run(&["edit", ROSA, "--set", "title=Ok", "--set", "pages=many"]);
+
+    assert_eq!(field.status.code(), Some(66));
+    assert_eq!(value.status.code(), Some(66));
+    assert!(sandbox.library().get("overrides").is_none());
+}
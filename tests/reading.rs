@@ -0,0 +1,160 @@
+mod common;
+
+use common::start;
+use serde_json::json;
+
+const ALGORITHMS: &str = "9780262033848";
+const ROSA: &str = "9788845292613";
+
+#[test]
+fn updates_the_reading_log() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+
+    let output = sandbox.run(&[
+        "reading",
+        ROSA,
+        "--status",
+        "read",
+        "--rating",
+        "5",
+        "--started",
+        "2024-01-10",
+        "--finished",
+        "2024-02-01",
+        "--quote",
+        "Stat rosa pristina nomine",
+    ]);
+    sandbox.run(&["reading", ROSA, "--notes", "Great ending"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        sandbox.library()["reading"][ROSA],
+        json!({
+            "status": "read",
+            "rating": 5,
+            "started": "2024-01-10",
+            "finished": "2024-02-01",
+            "notes": "Great ending",
+            "quotes": ["Stat rosa pristina nomine"]
+        })
+    );
+}
+
+#[test]
+fn rejects_invalid_ratings_and_dates() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+
+    let rating = sandbox.run(&["reading", ROSA, "--rating", "6"]);
+    let date = sandbox.run(&["reading", ROSA, "--started", "10/01/2024"]);
+
+    let day = sandbox.run(&["reading", ROSA, "--started", "2024-02-30"]);
+    let leap = sandbox.run(&["reading", ROSA, "--started", "2023-02-29"]);
+
+    assert_eq!(rating.status.code(), Some(2));
+    assert_eq!(date.status.code(), Some(2));
+    assert_eq!(day.status.code(), Some(2));
+    assert_eq!(leap.status.code(), Some(2));
+    assert!(sandbox.library().get("reading").is_none());
+
+    let output = sandbox.run(&["reading", ROSA, "--started", "2024-02-29"]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn rejects_a_finish_date_before_the_start() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+    sandbox.run(&["reading", ROSA, "--started", "2024-01-10"]);
+
+    let output = sandbox.run(&["reading", ROSA, "--finished", "2024-01-09"]);
+    let both = sandbox.run(&[
+        "reading",
+        ROSA,
+        "--started",
+        "2024-03-01",
+        "--finished",
+        "2024-02-01",
+    ]);
+
+    assert_eq!(output.status.code(), Some(66));
+    assert_eq!(both.status.code(), Some(66));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Finished on 2024-01-09, before the start on 2024-01-10"));
+    assert_eq!(
+        sandbox.library()["reading"][ROSA],
+        json!({ "started": "2024-01-10" })
+    );
+}
+
+#[test]
+fn clears_reading_fields() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ROSA]);
+    sandbox.run(&[
+        "reading",
+        ROSA,
+        "--rating",
+        "2",
+        "--started",
+        "2024-01-10",
+        "--notes",
+        "Too slow",
+    ]);
+
+    let output = sandbox.run(&[
+        "reading", ROSA, "--clear", "rating", "--clear", "notes", "--rating", "4",
+    ]);
+    sandbox.run(&["reading", ROSA, "--clear", "started"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(sandbox.library()["reading"][ROSA], json!({ "rating": 4 }));
+}
+
+#[test]
+fn fails_on_books_not_in_the_library() {
+    let (_server, sandbox) = start();
+
+    let output = sandbox.run(&["reading", ROSA, "--rating", "3"]);
+
+    assert_eq!(output.status.code(), Some(66));
+}
+
+#[test]
+fn filters_books_by_reading_status() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS, ROSA]);
+    sandbox.run(&["reading", ROSA, "--status", "reading"]);
+
+    let output = sandbox.run(&["list", "--status", "reading"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.contains("Il nome della rosa"));
+    assert!(stdout.contains("[reading]"));
+}
+
+#[test]
+fn refresh_keeps_the_user_data() {
+    let (server, sandbox) = start();
+    sandbox.run(&["--tag", "novel", ROSA]);
+    sandbox.run(&["reading", ROSA, "--status", "read", "--rating", "4"]);
+    let before = sandbox.library();
+
+    let output = sandbox.run(&["refresh"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let library = sandbox.library();
+    assert_eq!(library["volumes"][0]["title"], "Il nome della rosa");
+    assert_eq!(library["tags"], before["tags"]);
+    assert_eq!(library["reading"], before["reading"]);
+    assert_eq!(
+        server
+            .requests()
+            .iter()
+            .filter(|url| url.contains(ROSA))
+            .count(),
+        2
+    );
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::{fixtures_dir, start};
+use serde_json::Value;
+use std::fs::{read_dir, read_to_string};
+
+#[test]
+fn records_responses_as_fixtures() {
+    let (_server, sandbox) = start();
+    sandbox.configure(serde_json::json!({ "api_key": "SECRET" }));
+
+    let output = sandbox.run(&["--record", "recorded", "9788845292613"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let recorded = sandbox.path("recorded");
+    let mut names: Vec<String> = read_dir(&recorded)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "volumes-projection-full-q-isbn-3A9788845292613.json",
+            "volumes-xMBrDwAAQBAJ.json",
+        ]
+    );
+
+    // Recording the mock answers gives back the fixtures served
+    for name in names.iter() {
+        let data = read_to_string(recorded.join(name)).unwrap();
+        assert!(!data.contains("SECRET"));
+
+        let recorded: Value = serde_json::from_str(&data).unwrap();
+        let fixture: Value =
+            serde_json::from_str(&read_to_string(fixtures_dir().join(name)).unwrap())
+                .unwrap();
+        assert_eq!(recorded, fixture);
+    }
+}
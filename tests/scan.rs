@@ -0,0 +1,56 @@
+mod common;
+
+use common::{start, titles};
+use std::io::Write;
+use std::process::Stdio;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+const ALGORITHMS: &str = "9780262033848";
+const NOT_FOUND: &str = "9780306406157";
+
+#[test]
+fn adds_the_scanned_books_and_warns_about_the_others() {
+    let (_server, sandbox) = start();
+    let input = format!("{0}\n{0}\nnot-an-isbn\n{1}\n", ALGORITHMS, NOT_FOUND);
+
+    let output = sandbox.run_with_input(&["scan"], &input);
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains(&format!("Added {}: ", ALGORITHMS)));
+    assert!(stdout.contains("Scanned codes: 4"));
+    assert!(stdout.contains("Added Volumes: 1"));
+    assert!(stdout.contains("Duplicates: 1"));
+    assert!(stdout.contains("Failed: 2"));
+    assert!(stderr.contains(&format!("Warning: {} already in the library", ALGORITHMS)));
+    assert!(stderr.contains("Warning: \"not-an-isbn\" is not a valid ISBN"));
+    assert!(stderr.contains(&format!("Warning: {} not found", NOT_FOUND)));
+    assert_eq!(titles(&sandbox.library()).len(), 1);
+}
+
+#[test]
+fn saves_each_book_as_soon_as_it_is_scanned() {
+    let (_server, sandbox) = start();
+    let mut child = sandbox
+        .command(&["scan"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("cannot run books");
+    let mut stdin = child.stdin.take().unwrap();
+    writeln!(stdin, "{}", ALGORITHMS).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while titles(&sandbox.library()).is_empty() && Instant::now() < deadline {
+        sleep(Duration::from_millis(50));
+    }
+    let saved = titles(&sandbox.library());
+    drop(stdin);
+    let status = child.wait().unwrap();
+
+    assert!(saved.contains_key(ALGORITHMS));
+    assert_eq!(status.code(), Some(0));
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::start;
+
+const TWO_TOWERS: &str = "9780547928203";
+const RETURN_OF_THE_KING: &str = "9780547928197";
+const FELLOWSHIP: &str = "9780547928210";
+const ALGORITHMS: &str = "9780262033848";
+
+#[test]
+fn reads_the_series_from_titles_and_subtitles() {
+    let (_server, sandbox) = start();
+
+    sandbox.run(&[TWO_TOWERS, RETURN_OF_THE_KING]);
+
+    let library = sandbox.library();
+    let volumes = library["volumes"].as_array().unwrap();
+    assert_eq!(volumes[0]["series"], "Lord of the Rings");
+    assert_eq!(volumes[0]["series_index"], 2);
+    assert_eq!(volumes[1]["series"], "Lord of the Rings");
+    assert_eq!(volumes[1]["series_index"], 3);
+}
+
+#[test]
+fn reads_only_the_position_from_the_provider_series_info() {
+    let (_server, sandbox) = start();
+
+    sandbox.run(&[FELLOWSHIP]);
+
+    let volume = &sandbox.library()["volumes"][0];
+    assert!(volume.get("series").is_none());
+    assert_eq!(volume["series_index"], 1);
+}
+
+#[test]
+fn lists_series_with_missing_volumes() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[RETURN_OF_THE_KING, TWO_TOWERS, ALGORITHMS]);
+
+    let output = sandbox.stdout(&["series"]);
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "Lord of the Rings (2 volumes)",
+            &format!(
+                "      2  {}  The Two Towers (The Lord of the Rings, #2)",
+                TWO_TOWERS
+            ),
+            &format!("      3  {}  The Return of the King", RETURN_OF_THE_KING),
+            "    missing: 1",
+        ]
+    );
+}
+
+#[test]
+fn sets_the_series_by_hand() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS, TWO_TOWERS]);
+
+    let output = sandbox.run(&[
+        "edit",
+        ALGORITHMS,
+        "--set",
+        "series=lord of the rings",
+        "--set",
+        "series_index=1",
+    ]);
+    let series = sandbox.stdout(&["series"]);
+    let invalid = sandbox.run(&["edit", ALGORITHMS, "--set", "series_index=first"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(series.starts_with("lord of the rings (2 volumes)\n"));
+    assert!(!series.contains("missing"));
+    assert_ne!(invalid.status.code(), Some(0));
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::{start, Sandbox};
+use serde_json::{json, Value};
+use std::fs::{read_to_string, write};
+
+// Three books of two authors, one of them spelled two ways,
+// and a book with most of its metadata missing
+//
+fn sample_library(sandbox: &Sandbox) {
+    let volume = |isbn: &str, author: &str, language: &str, date: &str, pages: i64| {
+        json!({
+            "isbn": { "value": isbn },
+            "title": format!("Book {}", isbn),
+            "description": "",
+            "publisher": "Bompiani",
+            "published_date": date,
+            "image": "",
+            "language": language,
+            "authors": [author],
+            "pages": pages
+        })
+    };
+    let library = json!({
+        "volumes": [
+            volume("9788845292613", "Umberto Eco", "it", "1980", 500),
+            volume("9788845246340", "Eco, Umberto", "it", "1988-10", 600),
+            volume("9780262033848", "Thomas H. Cormen", "en", "2009-07-31", 1312),
+            {
+                "isbn": { "value": "9780306406157" },
+                "title": "Untitled",
+                "description": "",
+                "publisher": "",
+                "published_date": "",
+                "image": "",
+                "language": "",
+                "authors": [],
+                "pages": 0
+            }
+        ],
+        "added": {
+            "9788845292613": "2024-03-02",
+            "9788845246340": "2024-03-15",
+            "9780262033848": "2024-04-01"
+        }
+    });
+    write(sandbox.path("library.json"), library.to_string()).unwrap();
+}
+
+#[test]
+fn computes_the_library_aggregates() {
+    let (_server, sandbox) = start();
+    sample_library(&sandbox);
+
+    let output = sandbox.stdout(&["stats", "--format", "json"]);
+    let stats: Value = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(stats["volumes"], 4);
+    assert_eq!(stats["pages"], 2412);
+    assert_eq!(stats["authors"], 2);
+    assert_eq!(
+        stats["languages"],
+        json!([
+            { "label": "it", "count": 2 },
+            { "label": "en", "count": 1 },
+            { "label": "unknown", "count": 1 }
+        ])
+    );
+    assert_eq!(
+        stats["decades"],
+        json!([
+            { "label": "1980s", "count": 2 },
+            { "label": "2000s", "count": 1 },
+            { "label": "unknown", "count": 1 }
+        ])
+    );
+    assert_eq!(
+        stats["by_author"][0],
+        json!({ "label": "Eco, Umberto", "count": 2 })
+    );
+    assert_eq!(
+        stats["added"],
+        json!([
+            { "label": "2024-03", "count": 2 },
+            { "label": "2024-04", "count": 1 },
+            { "label": "not recorded", "count": 1 }
+        ])
+    );
+    assert_eq!(
+        stats["completeness"][0],
+        json!({ "label": "title", "count": 4 })
+    );
+    assert_eq!(
+        stats["completeness"][5],
+        json!({ "label": "pages", "count": 3 })
+    );
+    assert_eq!(
+        stats["completeness"][6],
+        json!({ "label": "description", "count": 0 })
+    );
+}
+
+#[test]
+fn dates_the_books_apart_from_their_metadata() {
+    let (_server, sandbox) = start();
+    sandbox.run(&["9788845292613"]);
+
+    sandbox.run(&["refresh"]);
+
+    let library = sandbox.library();
+    assert!(library["volumes"][0].get("added").is_none());
+    assert!(library["added"]["9788845292613"].is_string());
+}
+
+#[test]
+fn prints_the_statistics_as_tables() {
+    let (_server, sandbox) = start();
+    sample_library(&sandbox);
+
+    let output = sandbox.stdout(&["stats", "--top", "1"]);
+
+    assert!(output.starts_with("Volumes  4\nPages    2412\nAuthors  2\n"));
+    assert!(output.contains("\nLanguages\n  it           2   50%\n"));
+    assert!(output.contains("\nPublishers\n  Bompiani      3   75%\n  (1 more)\n"));
+}
+
+#[test]
+fn writes_an_html_report() {
+    let (_server, sandbox) = start();
+    sample_library(&sandbox);
+    let path = sandbox.path("stats.html");
+
+    let output = sandbox.run(&["stats", "--html", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let html = read_to_string(&path).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h2>Decades of publication</h2>"));
+    assert!(html.contains(">Eco, Umberto</text>"));
+    assert!(!html.contains("<script"));
+    assert!(!html.contains(" src="));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::start;
+use serde_json::json;
+
+const ALGORITHMS: &str = "9780262033848";
+const ROSA: &str = "9788845292613";
+
+#[test]
+fn keeps_wished_books_out_of_the_library() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS]);
+
+    let output = sandbox.run(&["wish", "add", ROSA, "--note", "gift idea"]);
+    let wishes = sandbox.stdout(&["wish", "list"]);
+    let list = sandbox.stdout(&["list"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let library = sandbox.library();
+    assert_eq!(library["volumes"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        library["wishlist"][0]["volume"]["title"],
+        "Il nome della rosa"
+    );
+    assert_eq!(library["wishlist"][0]["note"], "gift idea");
+    assert!(wishes.contains(&format!("  {}  Il nome della rosa  (gift idea)", ROSA)));
+    assert!(!list.contains("Il nome della rosa"));
+}
+
+#[test]
+fn moves_acquired_books_to_the_library() {
+    let (server, sandbox) = start();
+    sandbox.run(&["wish", "add", ROSA]);
+    let fetched = server.requests().len();
+
+    let output = sandbox.run(&[
+        "wish",
+        "acquire",
+        ROSA,
+        "--id",
+        "B-1",
+        "--acquired",
+        "2024-03-02",
+        "--price",
+        "12.00 EUR",
+    ]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(server.requests().len(), fetched);
+    let library = sandbox.library();
+    assert!(library.get("wishlist").is_none());
+    assert_eq!(library["volumes"][0]["title"], "Il nome della rosa");
+    assert_eq!(
+        library["holdings"][ROSA],
+        json!([{ "id": "B-1", "acquired": "2024-03-02", "price": "12.00 EUR" }])
+    );
+}
+
+#[test]
+fn forgets_wishes_once_the_book_is_owned() {
+    let (_server, sandbox) = start();
+    sandbox.run(&["wish", "add", ROSA]);
+    sandbox.run(&["wish", "add", ALGORITHMS]);
+
+    sandbox.run(&[ROSA]);
+    sandbox.run(&["copy", "add", ALGORITHMS, "--id", "A-1"]);
+
+    let library = sandbox.library();
+    assert_eq!(library["volumes"].as_array().unwrap().len(), 2);
+    assert!(library.get("wishlist").is_none());
+}
+
+#[test]
+fn rejects_books_owned_or_not_wished() {
+    let (_server, sandbox) = start();
+    sandbox.run(&[ALGORITHMS]);
+
+    let owned = sandbox.run(&["wish", "add", ALGORITHMS]);
+    let not_wished = sandbox.run(&["wish", "acquire", ROSA]);
+
+    assert_eq!(owned.status.code(), Some(66));
+    assert_eq!(not_wished.status.code(), Some(66));
+    assert!(sandbox.library().get("wishlist").is_none());
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::{start, Sandbox};
+use serde_json::json;
+use std::fs::write;
+
+const ALGORITHMS: &str = "9780262033848";
+const ROSA: &str = "9788845292613";
+const MISMATCH: &str = "9781491927281";
+
+// Two records of "Il nome della rosa" and an unrelated book
+//
+fn library_with_a_duplicate(sandbox: &Sandbox) {
+    sandbox.run(&[ALGORITHMS, ROSA, MISMATCH]);
+    sandbox.run(&[
+        "edit",
+        MISMATCH,
+        "--set",
+        "title=Il Nome della Rosa: romanzo",
+        "--set",
+        "authors=Eco, Umberto",
+    ]);
+}
+
+#[test]
+fn reports_likely_duplicates() {
+    let (_server, sandbox) = start();
+    library_with_a_duplicate(&sandbox);
+
+    let output = sandbox.stdout(&["duplicates"]);
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "[1.00]");
+    assert!(lines[1].starts_with(&format!("    {}  Il nome della rosa", ROSA)));
+    assert!(lines[2].starts_with(&format!("    {}  Il Nome della Rosa", MISMATCH)));
+}
+
+#[test]
+fn merges_duplicates_in_one_work() {
+    let (_server, sandbox) = start();
+    library_with_a_duplicate(&sandbox);
+
+    let output = sandbox.run(&["merge", ROSA, MISMATCH]);
+    let works = sandbox.stdout(&["works"]);
+    let duplicates = sandbox.stdout(&["duplicates"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        sandbox.library()["works"],
+        json!({ ROSA: [MISMATCH, ROSA] })
+    );
+    assert_eq!(
+        works.lines().next(),
+        Some("Il nome della rosa (2 editions)")
+    );
+    assert!(duplicates.is_empty());
+
+    sandbox.run(&["unmerge", MISMATCH]);
+    assert!(sandbox.library().get("works").is_none());
+}
+
+#[test]
+fn refuses_works_of_a_single_edition() {
+    let (_server, sandbox) = start();
+    library_with_a_duplicate(&sandbox);
+
+    let output = sandbox.run(&["merge", ROSA, ROSA]);
+
+    assert_eq!(output.status.code(), Some(66));
+    assert!(sandbox.library().get("works").is_none());
+}
+
+#[test]
+fn groups_similar_books_in_a_work() {
+    let (_server, sandbox) = start();
+    library_with_a_duplicate(&sandbox);
+
+    let works = sandbox.stdout(&["works"]);
+    let strict = sandbox.run(&["works", "--min-score", "1"]);
+    let loose = sandbox.run(&["works", "--min-score", "0"]);
+
+    let lines: Vec<&str> = works.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "Il nome della rosa (2 editions)");
+    assert!(lines[1].contains(ROSA));
+    assert!(lines[2].contains(MISMATCH));
+    assert!(!works.contains(ALGORITHMS));
+    assert_eq!(String::from_utf8_lossy(&strict.stdout), works);
+    assert!(String::from_utf8_lossy(&loose.stdout).contains("(3 editions)"));
+    assert!(sandbox.library().get("works").is_none());
+}
+
+#[test]
+fn keeps_works_linked_apart_even_if_similar() {
+    let (_server, sandbox) = start();
+    let volume = |isbn: &str, work_id: &str| {
+        json!({
+            "isbn": { "value": isbn },
+            "title": "The Hobbit",
+            "description": "",
+            "publisher": "",
+            "published_date": "",
+            "image": "",
+            "language": "",
+            "authors": ["J. R. R. Tolkien"],
+            "pages": 0,
+            "work_id": work_id
+        })
+    };
+    let library = json!({
+        "volumes": [
+            volume("9780261103344", "first"),
+            volume("9780261102217", "first"),
+            volume("9788845292613", "second"),
+            volume("9780547928227", "second")
+        ]
+    });
+    write(sandbox.path("library.json"), library.to_string()).unwrap();
+
+    let works = sandbox.stdout(&["works"]);
+
+    assert_eq!(works.matches("(2 editions)").count(), 2);
+}
+
+#[test]
+fn groups_editions_by_provider_work_id() {
+    let (_server, sandbox) = start();
+    let volume = |isbn: &str, title: &str, work_id: &str| {
+        json!({
+            "isbn": { "value": isbn },
+            "title": title,
+            "description": "",
+            "publisher": "",
+            "published_date": "",
+            "image": "",
+            "language": "",
+            "authors": ["J. R. R. Tolkien"],
+            "pages": 0,
+            "work_id": work_id
+        })
+    };
+    let library = json!({
+        "volumes": [
+            volume("9780261103344", "The Hobbit", "hobbit"),
+            volume("9780261102217", "The Silmarillion", "silmarillion"),
+            volume("9788845292613", "Lo Hobbit", "hobbit")
+        ]
+    });
+    write(sandbox.path("library.json"), library.to_string()).unwrap();
+
+    let works = sandbox.stdout(&["works"]);
+
+    let lines: Vec<&str> = works.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "The Hobbit (2 editions)");
+    assert!(lines[2].contains("Lo Hobbit"));
+}